@@ -8,7 +8,7 @@ use test::{black_box, Bencher};
 
 use conllx::token::Features;
 
-static FEATURES: &'static [&'static str] = &[
+static FEATURES: &[&str] = &[
     "cat:regular+noun|case:nominative|number:plural",
     "cat:article|definite:true|case:nominative|number:singular|gender:feminine",
     "cat:proper+name|case:nominative|number:singular|gender:feminine",
@@ -20,11 +20,11 @@ static FEATURES: &'static [&'static str] = &[
 pub fn bench_as_map(b: &mut Bencher) {
     b.iter(|| {
         for f_str in FEATURES {
-            let features = Features::from_string(*f_str);
-            black_box(features.as_map());
-            black_box(features.as_map());
-            black_box(features.as_map());
-            black_box(features.as_map());
+            let features = Features::from(*f_str);
+            black_box(&*features);
+            black_box(&*features);
+            black_box(&*features);
+            black_box(&*features);
         }
     });
 }
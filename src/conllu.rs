@@ -0,0 +1,933 @@
+//! CoNLL-U format reader and writers.
+//!
+//! The CoNLL-U format is used by the Universal Dependencies treebanks.
+//! Sentences in this format are read into the same `Sentence` graphs as
+//! CoNLL-X sentences. The CoNLL-U columns are stored as follows:
+//!
+//! * UPOS: the coarse-grained part-of-speech tag (`Token::cpos`).
+//! * XPOS: the fine-grained part-of-speech tag (`Token::pos`).
+//! * FEATS: the features (`Token::features`), written as `Feat=Val`.
+//! * HEAD/DEPREL: the non-projective dependency graph (`Sentence::dep_graph`).
+//! * DEPS: the enhanced dependency graph (`Sentence::enhanced_dep_graph`).
+//! * MISC: miscellaneous annotations (`Token::misc`).
+//!
+//! Range lines, such as `3-4 zum`, are stored as multiword tokens
+//! (`Sentence::multiword_tokens`). Only the FORM and MISC columns of
+//! range lines are used. Empty nodes, such as `8.1`, are stored as
+//! empty nodes of the sentence (`Sentence::empty_nodes`).
+//!
+//! Since the CoNLL-U format does not have a projective dependency layer,
+//! the projective graph is not used by the reader and writer.
+
+use std::io;
+
+use crate::error::Error;
+use itertools::Itertools;
+
+use crate::error::{ReadError, ReadErrorKind};
+use crate::graph::{
+    DepTriple, EnhancedDepGraph, EnhancedDepTriple, MultiwordToken, Node, NodeId, Sentence,
+};
+use crate::io::{
+    add_edges, parse_form_field, parse_identifier_field, parse_numeric_field, parse_string_field,
+    LineReader, ReadSentence, Sentences, WriteSentence,
+};
+use crate::token::{key_values_to_string, parse_key_values, Features, Misc, Token, EMPTY_TOKEN};
+
+/// A reader for CoNLL-U sentences.
+pub struct Reader<R> {
+    lines: LineReader<R>,
+}
+
+impl<R: io::BufRead> Reader<R> {
+    /// Construct a new reader from an object that implements the
+    /// `io::BufRead` trait.
+    pub fn new(read: R) -> Reader<R> {
+        Reader {
+            lines: LineReader::new(read),
+        }
+    }
+
+    /// Enable or disable lenient reading.
+    ///
+    /// In lenient mode, a sentence that cannot be read is skipped and
+    /// reading resumes after the next blank line. The errors of skipped
+    /// sentences are available through `skipped`. I/O errors are always
+    /// returned.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lines.lenient = lenient;
+        self
+    }
+
+    /// Get the errors of the sentences that were skipped in lenient mode.
+    pub fn skipped(&self) -> &[ReadError] {
+        &self.lines.skipped
+    }
+}
+
+impl<R: io::BufRead> IntoIterator for Reader<R> {
+    type Item = Result<Sentence, Error>;
+    type IntoIter = Sentences<Reader<R>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sentences()
+    }
+}
+
+impl<R: io::BufRead> ReadSentence for Reader<R> {
+    fn read_sentence(&mut self) -> Result<Option<Sentence>, Error> {
+        loop {
+            match self.try_read_sentence() {
+                Err(err) => self.lines.recover(err)?,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: io::BufRead> Reader<R> {
+    fn try_read_sentence(&mut self) -> Result<Option<Sentence>, Error> {
+        let mut sentence = Sentence::new();
+        let mut pending = PendingRelations::default();
+
+        self.lines.start_sentence();
+
+        loop {
+            // End of reader.
+            if !self.lines.next_line()? {
+                if sentence.len() == 1 {
+                    return Ok(None);
+                }
+
+                self.finish_sentence(&mut sentence, pending)?;
+
+                return Ok(Some(sentence));
+            }
+
+            let line = self.lines.current();
+
+            // The blank line is a sentence separator.
+            if line.trim().is_empty() {
+                if sentence.len() == 1 {
+                    continue;
+                }
+
+                self.finish_sentence(&mut sentence, pending)?;
+
+                return Ok(Some(sentence));
+            }
+
+            // Comments are only permitted before the first token.
+            if sentence.len() == 1 && line.starts_with('#') {
+                sentence.add_comment(line[1..].trim());
+                continue;
+            }
+
+            parse_line(line, self.lines.line_number(), &mut sentence, &mut pending)
+                .map_err(|kind| self.lines.error(kind))?;
+        }
+    }
+
+    /// Add the relations and multiword tokens that can only be validated
+    /// once the sentence is read completely.
+    fn finish_sentence(
+        &self,
+        sentence: &mut Sentence,
+        pending: PendingRelations,
+    ) -> Result<(), ReadError> {
+        add_edges(&self.lines, sentence, pending.edges, Vec::new())?;
+
+        for (line, edge) in pending.enhanced_edges {
+            if sentence.node(edge.head()).is_none() {
+                return Err(self.lines.error_at(
+                    line,
+                    ReadErrorKind::HeadOutOfBounds {
+                        value: edge.head().to_string(),
+                    },
+                ));
+            }
+
+            sentence.enhanced_dep_graph_mut().add_deprel(edge);
+        }
+
+        for (line, multiword_token) in pending.multiword_tokens {
+            if multiword_token.last() >= sentence.len() {
+                return Err(self.lines.error_at(
+                    line,
+                    ReadErrorKind::InvalidMultiwordToken {
+                        value: format!("{}-{}", multiword_token.first(), multiword_token.last()),
+                    },
+                ));
+            }
+
+            sentence.add_multiword_token(multiword_token);
+        }
+
+        Ok(())
+    }
+}
+
+/// Relations and multiword tokens that are added after reading a sentence.
+///
+/// Relations and multiword tokens are stored with the number of the line
+/// that they were read from.
+#[derive(Default)]
+struct PendingRelations {
+    edges: Vec<(usize, DepTriple<String>)>,
+    enhanced_edges: Vec<(usize, EnhancedDepTriple<String>)>,
+    multiword_tokens: Vec<(usize, MultiwordToken)>,
+}
+
+/// Parse a CoNLL-U line that is not a comment.
+fn parse_line(
+    line: &str,
+    line_number: usize,
+    sentence: &mut Sentence,
+    pending: &mut PendingRelations,
+) -> Result<(), ReadErrorKind> {
+    let mut iter = line.trim().split_terminator('\t');
+
+    match parse_conllu_identifier_field(iter.next())? {
+        Identifier::Token => {
+            let parsed = parse_token(&mut iter, NodeId::Token(sentence.len()))?;
+            pending
+                .edges
+                .extend(parsed.head.map(|edge| (line_number, edge)));
+            pending.enhanced_edges.extend(
+                parsed
+                    .enhanced_heads
+                    .into_iter()
+                    .map(|edge| (line_number, edge)),
+            );
+
+            sentence.push(parsed.token);
+        }
+        Identifier::Range(first, last) => {
+            let multiword_token = parse_multiword_token(
+                sentence,
+                pending.multiword_tokens.last().map(|(_, mwt)| mwt),
+                first,
+                last,
+                iter,
+            )?;
+            pending
+                .multiword_tokens
+                .push((line_number, multiword_token));
+        }
+        Identifier::Empty(token, position) => {
+            let enhanced_heads = add_empty_node(sentence, token, position, iter)?;
+            pending
+                .enhanced_edges
+                .extend(enhanced_heads.into_iter().map(|edge| (line_number, edge)));
+        }
+    }
+
+    Ok(())
+}
+
+/// CoNLL-U token identifiers.
+enum Identifier {
+    /// Identifier of a token in the dependency graph.
+    Token,
+
+    /// Identifier of the range of a multiword token.
+    Range(usize, usize),
+
+    /// Identifier of an empty node.
+    Empty(usize, usize),
+}
+
+/// The fields of a token or empty node.
+struct ParsedToken {
+    token: Token,
+    head: Option<DepTriple<String>>,
+    enhanced_heads: Vec<EnhancedDepTriple<String>>,
+}
+
+/// Parse the fields of an empty node and add it to the sentence.
+///
+/// An empty node should follow the token or empty node that precedes
+/// it. Empty nodes do not have a head in the dependency graph.
+///
+/// Returns the enhanced head relations of the empty node.
+fn add_empty_node<'a>(
+    sentence: &mut Sentence,
+    token: usize,
+    position: usize,
+    mut iter: impl Iterator<Item = &'a str>,
+) -> Result<Vec<EnhancedDepTriple<String>>, ReadErrorKind> {
+    let expected_position = sentence
+        .empty_nodes()
+        .filter(|(id, _)| id.token() == token)
+        .count()
+        + 1;
+
+    let id = NodeId::Empty(token, position);
+    let parsed = parse_token(&mut iter, id)?;
+    if token + 1 != sentence.len() || position != expected_position || parsed.head.is_some() {
+        return Err(ReadErrorKind::InvalidEmptyNode {
+            value: id.to_string(),
+        });
+    }
+
+    sentence.add_empty_node(token, parsed.token);
+
+    Ok(parsed.enhanced_heads)
+}
+
+fn parse_conllu_identifier_field(field: Option<&str>) -> Result<Identifier, ReadErrorKind> {
+    if let Some(s) = field {
+        // The identifier parts are always present, parse_identifier_field
+        // returns an error otherwise.
+        if let Some(idx) = s.find('-') {
+            let first = parse_identifier_field(Some(&s[..idx]))?;
+            let last = parse_identifier_field(Some(&s[idx + 1..]))?;
+            return Ok(Identifier::Range(first.unwrap(), last.unwrap()));
+        }
+
+        if let Some(idx) = s.find('.') {
+            let token = parse_identifier_field(Some(&s[..idx]))?;
+            let position = parse_identifier_field(Some(&s[idx + 1..]))?;
+            return Ok(Identifier::Empty(token.unwrap(), position.unwrap()));
+        }
+    }
+
+    parse_identifier_field(field)?;
+
+    Ok(Identifier::Token)
+}
+
+/// Parse the enhanced dependencies field (`head:deprel|head:deprel`).
+fn parse_deps_field(
+    field: Option<&str>,
+    dependent: NodeId,
+) -> Result<Vec<EnhancedDepTriple<String>>, ReadErrorKind> {
+    let field = match field {
+        Some(field) if field != EMPTY_TOKEN => field,
+        _ => return Ok(Vec::new()),
+    };
+
+    field
+        .split('|')
+        .map(|head_rel| {
+            let (head, rel) = match head_rel.find(':') {
+                Some(idx) => (&head_rel[..idx], Some(head_rel[idx + 1..].to_owned())),
+                None => (head_rel, None),
+            };
+
+            let head = parse_node_id(head).ok_or_else(|| ReadErrorKind::ParseDepsField {
+                value: field.to_owned(),
+            })?;
+
+            Ok(EnhancedDepTriple::new(head, rel, dependent))
+        })
+        .collect()
+}
+
+/// Parse a token (`8`) or empty node (`8.1`) identifier.
+fn parse_node_id(id: &str) -> Option<NodeId> {
+    match id.find('.') {
+        Some(idx) => Some(NodeId::Empty(
+            id[..idx].parse().ok()?,
+            id[idx + 1..].parse().ok()?,
+        )),
+        None => id.parse().ok().map(NodeId::Token),
+    }
+}
+
+/// Parse the fields of a token or empty node, except for the identifier.
+fn parse_token<'a>(
+    iter: &mut impl Iterator<Item = &'a str>,
+    id: NodeId,
+) -> Result<ParsedToken, ReadErrorKind> {
+    let mut token = Token::new(parse_form_field(iter.next())?);
+    token.set_lemma(parse_string_field(iter.next()));
+    token.set_cpos(parse_string_field(iter.next()));
+    token.set_pos(parse_string_field(iter.next()));
+    token.set_features(parse_string_field(iter.next()).map(|s| parse_features(&s)));
+
+    // Head relation.
+    let head = parse_numeric_field(iter.next())?;
+    let head_rel = parse_string_field(iter.next());
+
+    let enhanced_heads = parse_deps_field(iter.next(), id)?;
+    token.set_misc(parse_string_field(iter.next()).map(|s| Misc::from(s.as_str())));
+
+    Ok(ParsedToken {
+        token,
+        head: head.map(|head| DepTriple::new(head, head_rel, id.token())),
+        enhanced_heads,
+    })
+}
+
+/// Parse the fields of a multiword token.
+///
+/// A multiword token should precede the first token that it covers and
+/// should not overlap with the previous multiword token.
+fn parse_multiword_token<'a>(
+    sentence: &Sentence,
+    previous: Option<&MultiwordToken>,
+    first: usize,
+    last: usize,
+    mut iter: impl Iterator<Item = &'a str>,
+) -> Result<MultiwordToken, ReadErrorKind> {
+    let overlaps = previous.map(|prev| prev.last() >= first).unwrap_or(false);
+
+    if first != sentence.len() || last <= first || overlaps {
+        return Err(ReadErrorKind::InvalidMultiwordToken {
+            value: format!("{}-{}", first, last),
+        });
+    }
+
+    let mut multiword_token = MultiwordToken::new(first, last, parse_form_field(iter.next())?);
+
+    // Only the form and miscellaneous fields are used for multiword tokens.
+    multiword_token.set_misc(parse_string_field(iter.nth(7)).map(|s| Misc::from(s.as_str())));
+
+    Ok(multiword_token)
+}
+
+/// A writer for CoNLL-U sentences.
+///
+/// This writer will write sentences to the embedded writer in CoNLL-U
+/// tabular format.
+pub struct Writer<W> {
+    write: W,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Construct a new writer from an object that implements the `io::Write`
+    /// trait.
+    pub fn new(write: W) -> Writer<W> {
+        Writer { write }
+    }
+
+    /// Borrow the embedded writer. Getting the underlying writer is often
+    /// useful when the writer writes to a memory object.
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    fn write_token(
+        &mut self,
+        id: NodeId,
+        token: &Token,
+        head: Option<(usize, Option<&str>)>,
+        enhanced_graph: &EnhancedDepGraph,
+    ) -> Result<(), io::Error> {
+        let mut deps = enhanced_graph
+            .heads(id)
+            .map(|triple| match triple.relation() {
+                Some(relation) => format!("{}:{}", triple.head(), relation),
+                None => triple.head().to_string(),
+            })
+            .join("|");
+        if deps.is_empty() {
+            deps.push_str(EMPTY_TOKEN);
+        }
+
+        writeln!(
+            self.write,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            id,
+            token.form(),
+            token.lemma().unwrap_or(EMPTY_TOKEN),
+            token.cpos().unwrap_or(EMPTY_TOKEN),
+            token.pos().unwrap_or(EMPTY_TOKEN),
+            token
+                .features()
+                .map(features_to_string)
+                .unwrap_or_else(|| EMPTY_TOKEN.to_string()),
+            head.map(|(head, _)| head.to_string())
+                .unwrap_or_else(|| EMPTY_TOKEN.to_string()),
+            head.and_then(|(_, rel)| rel).unwrap_or(EMPTY_TOKEN),
+            deps,
+            token
+                .misc()
+                .map(Into::into)
+                .unwrap_or_else(|| EMPTY_TOKEN.to_string()),
+        )
+    }
+}
+
+impl<W: io::Write> WriteSentence for Writer<W> {
+    fn write_sentence(&mut self, sentence: &Sentence) -> Result<(), Error> {
+        let dep_graph = sentence.dep_graph();
+        let enhanced_graph = sentence.enhanced_dep_graph();
+
+        for comment in sentence.comments() {
+            writeln!(self.write, "# {}", comment)?;
+        }
+
+        let mut multiword_tokens = sentence.multiword_tokens().iter().peekable();
+        let mut empty_nodes = sentence.empty_nodes().peekable();
+
+        for i in 0..sentence.len() {
+            if let Some(multiword_token) = multiword_tokens.next_if(|mwt| mwt.first() == i) {
+                writeln!(
+                    self.write,
+                    "{}-{}\t{}\t_\t_\t_\t_\t_\t_\t_\t{}",
+                    multiword_token.first(),
+                    multiword_token.last(),
+                    multiword_token.form(),
+                    multiword_token
+                        .misc()
+                        .map(Into::into)
+                        .unwrap_or_else(|| EMPTY_TOKEN.to_string()),
+                )?;
+            }
+
+            match sentence[i] {
+                Node::Token(ref token) => {
+                    let head_triple = dep_graph.head(i);
+                    self.write_token(
+                        NodeId::Token(i),
+                        token,
+                        head_triple.as_ref().map(|t| (t.head(), t.relation())),
+                        &enhanced_graph,
+                    )?;
+                }
+                Node::Root => (),
+                Node::Empty(_) => unreachable!(),
+            }
+
+            while let Some((id, empty_node)) = empty_nodes.next_if(|(id, _)| id.token() == i) {
+                // Empty nodes always have a token.
+                self.write_token(id, empty_node.token().unwrap(), None, &enhanced_graph)?;
+            }
+        }
+
+        // CoNLL-U requires every sentence, including the last one, to be
+        // terminated by a blank line.
+        writeln!(self.write)?;
+
+        Ok(())
+    }
+}
+
+/// Parse features in the CoNLL-U `Feat=Val` format.
+fn parse_features(feature_string: &str) -> Features {
+    Features::from(parse_key_values(feature_string, '='))
+}
+
+/// Convert features to the CoNLL-U `Feat=Val` format.
+fn features_to_string(features: &Features) -> String {
+    key_values_to_string(&**features, '=')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{BufRead, BufReader, Cursor, Read};
+    use std::str;
+
+    use lazy_static::lazy_static;
+
+    use super::{Reader, Writer};
+    use crate::error::{Error, ReadErrorKind};
+    use crate::graph::{DepTriple, EnhancedDepTriple, MultiwordToken, Node, NodeId, Sentence};
+    use crate::io::{ReadSentence, WriteSentence};
+    use crate::token::{Features, Misc, TokenBuilder};
+
+    static BASIC: &str = "testdata/basic.conllu";
+
+    static MULTIWORD: &str = "testdata/multiword.conllu";
+
+    static EMPTY_NODES: &str = "testdata/empty-nodes.conllu";
+
+    lazy_static! {
+        static ref TEST_SENTENCES: Vec<Sentence> = {
+            let mut sentences = Vec::new();
+
+            let mut s1 = Sentence::new();
+            s1.add_comment("sent_id = 1");
+            s1.add_comment("text = Die Großaufnahme.");
+            s1.push(
+                TokenBuilder::new("Die")
+                    .lemma("der")
+                    .cpos("DET")
+                    .pos("ART")
+                    .features(Features::from("Case:Nom|Definite:Def|Number:Sing"))
+                    .into(),
+            );
+            s1.push(
+                TokenBuilder::new("Großaufnahme")
+                    .lemma("Großaufnahme")
+                    .cpos("NOUN")
+                    .pos("NN")
+                    .features(Features::from("Case:Nom|Gender:Fem|Number:Sing"))
+                    .misc(Misc::from("SpaceAfter=No"))
+                    .into(),
+            );
+            s1.push(
+                TokenBuilder::new(".")
+                    .lemma(".")
+                    .cpos("PUNCT")
+                    .pos("$.")
+                    .into(),
+            );
+            s1.dep_graph_mut()
+                .add_deprel(DepTriple::new(2, Some("det"), 1));
+            s1.dep_graph_mut()
+                .add_deprel(DepTriple::new(0, Some("root"), 2));
+            s1.dep_graph_mut()
+                .add_deprel(DepTriple::new(2, Some("punct"), 3));
+            s1.enhanced_dep_graph_mut()
+                .add_deprel(EnhancedDepTriple::new(2, Some("det"), 1));
+            s1.enhanced_dep_graph_mut()
+                .add_deprel(EnhancedDepTriple::new(0, Some("root"), 2));
+            s1.enhanced_dep_graph_mut()
+                .add_deprel(EnhancedDepTriple::new(2, Some("punct"), 3));
+            sentences.push(s1);
+
+            let mut s2 = Sentence::new();
+            s2.add_comment("sent_id = 2");
+            s2.add_comment("text = Gilles Deleuze");
+            s2.push(
+                TokenBuilder::new("Gilles")
+                    .lemma("Gilles")
+                    .cpos("PROPN")
+                    .pos("NE")
+                    .features(Features::from("Case:Nom|Gender:Masc|Number:Sing"))
+                    .into(),
+            );
+            s2.push(
+                TokenBuilder::new("Deleuze")
+                    .lemma("Deleuze")
+                    .cpos("PROPN")
+                    .pos("NE")
+                    .misc(Misc::from("Entity=Person|Uncertain"))
+                    .into(),
+            );
+            s2.dep_graph_mut()
+                .add_deprel(DepTriple::new(0, Some("root"), 1));
+            s2.dep_graph_mut()
+                .add_deprel(DepTriple::new(1, Some("flat"), 2));
+            sentences.push(s2);
+
+            sentences
+        };
+    }
+
+    fn read_file(filename: &str) -> String {
+        let mut f = File::open(filename).unwrap();
+        let mut contents = String::new();
+        f.read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    fn read_sentences(filename: &str) -> Vec<Sentence> {
+        Reader::new(BufReader::new(File::open(filename).unwrap()))
+            .sentences()
+            .map(|s| s.unwrap())
+            .collect()
+    }
+
+    fn string_reader(s: &str) -> Box<dyn BufRead> {
+        Box::new(Cursor::new(s.as_bytes().to_owned()))
+    }
+
+    #[test]
+    fn reader() {
+        let sentences = read_sentences(BASIC);
+        assert_eq!(*TEST_SENTENCES, sentences);
+    }
+
+    #[test]
+    fn reader_metadata() {
+        let sentences = read_sentences(BASIC);
+        assert_eq!(sentences[1].metadata().get("sent_id"), Some(&"2"));
+        assert_eq!(sentences[1].metadata().get("text"), Some(&"Gilles Deleuze"));
+    }
+
+    #[test]
+    fn reader_conllu_columns() {
+        let sentences = read_sentences(BASIC);
+        let token = sentences[0][2].token().unwrap();
+        assert_eq!(token.cpos(), Some("NOUN"));
+        assert_eq!(token.pos(), Some("NN"));
+        assert_eq!(
+            token
+                .features()
+                .unwrap()
+                .get("Gender")
+                .unwrap()
+                .as_ref()
+                .map(String::as_str),
+            Some("Fem")
+        );
+        assert_eq!(
+            sentences[0]
+                .enhanced_dep_graph()
+                .heads(2)
+                .collect::<Vec<_>>(),
+            &[EnhancedDepTriple::new(0, Some("root"), 2)]
+        );
+        assert_eq!(
+            token
+                .misc()
+                .unwrap()
+                .get("SpaceAfter")
+                .unwrap()
+                .as_ref()
+                .map(String::as_str),
+            Some("No")
+        );
+        assert!(sentences[0].proj_dep_graph().head(2).is_none());
+    }
+
+    #[test]
+    fn reader_multiword_tokens() {
+        let sentences = read_sentences(MULTIWORD);
+        assert_eq!(sentences.len(), 1);
+
+        let sentence = &sentences[0];
+        assert_eq!(sentence.len(), 9);
+
+        let mut zum = MultiwordToken::new(3, 4, "zum");
+        zum.set_misc(Some(Misc::from("SpaceAfter=No")));
+        assert_eq!(
+            sentence.multiword_tokens(),
+            &[zum, MultiwordToken::new(6, 7, "im")]
+        );
+
+        assert!(sentence.multiword_token(2).is_none());
+        assert_eq!(
+            sentence.multiword_token(4).map(MultiwordToken::form),
+            Some("zum")
+        );
+        assert_eq!(
+            sentence.multiword_token(6).map(MultiwordToken::form),
+            Some("im")
+        );
+        assert_eq!(sentence[5].token().map(|t| t.form()), Some("Bahnhof"));
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidMultiwordToken")]
+    fn reader_rejects_misplaced_multiword_token() {
+        let mut reader = Reader::new(string_reader(
+            "1\tzu\t_\t_\t_\t_\t_\t_\t_\t_\n1-2\tzum\t_\t_\t_\t_\t_\t_\t_\t_\n2\tdem\t_\t_\t_\t_\t_\t_\t_\t_",
+        ));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidMultiwordToken")]
+    fn reader_rejects_out_of_bounds_multiword_token() {
+        let mut reader = Reader::new(string_reader(
+            "1-2\tzum\t_\t_\t_\t_\t_\t_\t_\t_\n1\tzu\t_\t_\t_\t_\t_\t_\t_\t_",
+        ));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    fn reader_empty_nodes() {
+        let sentences = read_sentences(EMPTY_NODES);
+        assert_eq!(sentences.len(), 1);
+
+        let sentence = &sentences[0];
+        assert_eq!(sentence.len(), 8);
+
+        let empty_nodes = sentence.empty_nodes().collect::<Vec<_>>();
+        assert_eq!(empty_nodes.len(), 1);
+        assert_eq!(empty_nodes[0].0, NodeId::Empty(5, 1));
+
+        let empty_node = sentence.node(NodeId::Empty(5, 1)).unwrap();
+        assert!(empty_node.is_empty_node());
+        assert_eq!(empty_node.token().map(|t| t.form()), Some("likes"));
+
+        // Empty nodes participate in the enhanced graph.
+        let enhanced_graph = sentence.enhanced_dep_graph();
+        assert_eq!(
+            enhanced_graph
+                .heads(NodeId::Empty(5, 1))
+                .collect::<Vec<_>>(),
+            &[EnhancedDepTriple::new(
+                2,
+                Some("conj:and"),
+                NodeId::Empty(5, 1)
+            )]
+        );
+        assert_eq!(
+            enhanced_graph.heads(1).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(2, Some("nsubj"), 1),
+                EnhancedDepTriple::new(NodeId::Empty(5, 1), Some("nsubj"), 1)
+            ]
+        );
+        let mut dependents = enhanced_graph
+            .dependents(NodeId::Empty(5, 1))
+            .map(|triple| triple.dependent())
+            .collect::<Vec<_>>();
+        dependents.sort();
+        assert_eq!(
+            dependents,
+            &[
+                NodeId::Token(1),
+                NodeId::Token(4),
+                NodeId::Token(5),
+                NodeId::Token(6)
+            ]
+        );
+
+        // Empty nodes are not part of the dependency graph.
+        assert_eq!(sentence[6].token().map(|t| t.form()), Some("tea"));
+        assert_eq!(
+            sentence.dep_graph().head(6),
+            Some(DepTriple::new(5, Some("orphan"), 6))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidEmptyNode")]
+    fn reader_rejects_misplaced_empty_node() {
+        let mut reader = Reader::new(string_reader(
+            "1\ta\t_\t_\t_\t_\t_\t_\t_\t_\n2\tb\t_\t_\t_\t_\t_\t_\t_\t_\n1.1\tc\t_\t_\t_\t_\t_\t_\t_\t_",
+        ));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidEmptyNode")]
+    fn reader_rejects_empty_node_with_head() {
+        let mut reader = Reader::new(string_reader(
+            "1\ta\t_\t_\t_\t_\t0\troot\t_\t_\n1.1\tb\t_\t_\t_\t_\t1\tdep\t_\t_",
+        ));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "HeadOutOfBounds")]
+    fn reader_rejects_out_of_bounds_enhanced_head() {
+        let mut reader = Reader::new(string_reader(
+            "1\ta\t_\t_\t_\t_\t0\troot\t0:root|1.1:dep\t_",
+        ));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    fn reader_deferred_error_position() {
+        let mut reader = Reader::new(string_reader(
+            "# sent_id = 1\n1\ta\t_\t_\t_\t_\t0\troot\t0:root|3:dep\t_\n2\tb\t_\t_\t_\t_\t1\tdep\t1:dep\t_\n",
+        ));
+
+        let err = match reader.read_sentence() {
+            Err(Error::Read(err)) => err,
+            _ => panic!("expected a read error"),
+        };
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.sentence(), 1);
+        assert_eq!(err.content(), "1\ta\t_\t_\t_\t_\t0\troot\t0:root|3:dep\t_");
+        assert_eq!(
+            err.kind(),
+            &ReadErrorKind::HeadOutOfBounds {
+                value: "3".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reader_lenient() {
+        let mut reader = Reader::new(string_reader(
+            "1-2\tzum\t_\t_\t_\t_\t_\t_\t_\t_\n1\tzu\t_\t_\t_\t_\t_\t_\t_\t_\n\n1\ta\t_\t_\t_\t_\t0\troot\t_\t_\n",
+        ))
+        .lenient(true);
+
+        let sentence = reader.read_sentence().unwrap().unwrap();
+        assert_eq!(sentence[1].token().map(|t| t.form()), Some("a"));
+        assert!(reader.read_sentence().unwrap().is_none());
+
+        assert_eq!(reader.skipped().len(), 1);
+        assert_eq!(reader.skipped()[0].line(), 1);
+        assert_eq!(
+            reader.skipped()[0].kind(),
+            &ReadErrorKind::InvalidMultiwordToken {
+                value: "1-2".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ParseDepsField")]
+    fn reader_rejects_invalid_deps() {
+        let mut reader = Reader::new(string_reader("1\ta\t_\t_\t_\t_\t0\troot\tx:root\t_"));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "ParseIntField")]
+    fn reader_rejects_non_numeric_head() {
+        let mut reader = Reader::new(string_reader("1\tTest\t_\t_\t_\t_\tx\t_\t_\t_"));
+        reader.read_sentence().unwrap();
+    }
+
+    #[test]
+    fn writer() {
+        let mut writer = Writer::new(Vec::new());
+
+        for sentence in &*TEST_SENTENCES {
+            writer.write_sentence(sentence).unwrap();
+        }
+
+        assert_eq!(read_file(BASIC), str::from_utf8(writer.get_ref()).unwrap());
+    }
+
+    #[test]
+    fn writer_empty_nodes() {
+        let mut writer = Writer::new(Vec::new());
+
+        for sentence in read_sentences(EMPTY_NODES) {
+            writer.write_sentence(&sentence).unwrap();
+        }
+
+        assert_eq!(
+            read_file(EMPTY_NODES),
+            str::from_utf8(writer.get_ref()).unwrap()
+        );
+
+        let mut sentence = Sentence::new();
+        sentence.add_empty_node(0, TokenBuilder::new("a").into());
+        sentence.push(TokenBuilder::new("b").into());
+        let mut writer = Writer::new(Vec::new());
+        writer.write_sentence(&sentence).unwrap();
+        assert_eq!(
+            "0.1\ta\t_\t_\t_\t_\t_\t_\t_\t_\n1\tb\t_\t_\t_\t_\t_\t_\t_\t_\n\n",
+            str::from_utf8(writer.get_ref()).unwrap()
+        );
+        assert!(matches!(
+            sentence.node(NodeId::Empty(0, 1)),
+            Some(Node::Empty(_))
+        ));
+    }
+
+    #[test]
+    fn writer_preserves_misc() {
+        let conllu = "1\tHallo\t_\t_\t_\t_\t_\t_\t_\tTranslit=Hallo|SpaceAfter=No|Gloss=hello|Gloss=hi|Flag\n\n";
+        let sentence = Reader::new(string_reader(conllu))
+            .read_sentence()
+            .unwrap()
+            .unwrap();
+        let misc = sentence[1].token().unwrap().misc().unwrap();
+        assert_eq!(misc.get("Gloss"), Some(&Some("hello".to_owned())));
+        assert_eq!(misc.len(), 5);
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_sentence(&sentence).unwrap();
+        assert_eq!(conllu, str::from_utf8(writer.get_ref()).unwrap());
+    }
+
+    #[test]
+    fn writer_multiword_tokens() {
+        let mut writer = Writer::new(Vec::new());
+
+        for sentence in read_sentences(MULTIWORD) {
+            writer.write_sentence(&sentence).unwrap();
+        }
+
+        assert_eq!(
+            read_file(MULTIWORD),
+            str::from_utf8(writer.get_ref()).unwrap()
+        );
+    }
+}
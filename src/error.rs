@@ -1,25 +1,185 @@
-use failure::Fail;
+use std::io;
+
+use thiserror::Error;
+
+/// Errors of this crate.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An I/O error.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// The input could not be read as a sentence.
+    #[error(transparent)]
+    Read(#[from] ReadError),
+
+    /// A dependency graph is not consistent.
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    /// Gold and system sentences cannot be compared.
+    #[error(transparent)]
+    Eval(#[from] EvalError),
+
+    /// A query could not be compiled.
+    #[error(transparent)]
+    Query(#[from] QueryError),
+}
 
 /// CoNLL-X read errors.
-#[derive(Debug, Fail)]
-pub enum ReadError {
+///
+/// A read error records where in the input the error occurred: the
+/// line number, the ordinal of the sentence that was being read, and
+/// the content of the offending line. Line numbers and sentence
+/// ordinals start at 1.
+#[derive(Debug, Error)]
+#[error("line {line} (sentence {sentence}): {kind}")]
+pub struct ReadError {
+    line: usize,
+    sentence: usize,
+    content: String,
+    kind: ReadErrorKind,
+}
+
+impl ReadError {
+    pub(crate) fn new(
+        line: usize,
+        sentence: usize,
+        content: impl Into<String>,
+        kind: ReadErrorKind,
+    ) -> Self {
+        ReadError {
+            line,
+            sentence,
+            content: content.into(),
+            kind,
+        }
+    }
+
+    /// Get the content of the offending line.
+    ///
+    /// The line terminator is not included.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Get the kind of error.
+    pub fn kind(&self) -> &ReadErrorKind {
+        &self.kind
+    }
+
+    /// Get the number of the offending line.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the ordinal of the sentence in which the error occurred.
+    pub fn sentence(&self) -> usize {
+        self.sentence
+    }
+}
+
+/// The kinds of CoNLL-X read errors.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ReadErrorKind {
     /// The form is missing in the CoNLL-X data.
-    #[fail(display = "form field is missing")]
+    #[error("form field is missing")]
     MissingFormField,
 
     /// An integer field could not be parsed as an integer.
-    #[fail(display = "cannot parse as integer field: {}", value)]
+    #[error("cannot parse as integer field: {value}")]
     ParseIntField { value: String },
 
+    /// A token identifier does not follow the previous identifier.
+    #[error("token identifier is not sequential: {value}")]
+    NonSequentialIdentifier { value: String },
+
+    /// A token line does not have the expected number of columns.
+    #[error("invalid number of columns: {value}")]
+    InvalidColumnCount { value: String },
+
+    /// A column is empty or contains whitespace.
+    #[error("columns should be separated by a single tab: {value:?}")]
+    InvalidSeparator { value: String },
+
     /// The identifier field could not be parsed.
-    #[fail(display = "cannot parse as identifier field: {}", value)]
+    #[error("cannot parse as identifier field: {value}")]
     ParseIdentifierField { value: String },
+
+    /// The enhanced dependencies field could not be parsed.
+    #[error("cannot parse as enhanced dependencies field: {value}")]
+    ParseDepsField { value: String },
+
+    /// A head refers to a node that is not in the sentence.
+    #[error("head is out of bounds: {value}")]
+    HeadOutOfBounds { value: String },
+
+    /// The range of a multiword token is invalid.
+    #[error("invalid multiword token range: {value}")]
+    InvalidMultiwordToken { value: String },
+
+    /// An empty node is out of order or has a head.
+    #[error("invalid empty node: {value}")]
+    InvalidEmptyNode { value: String },
 }
 
 /// Graph errors.
-#[derive(Debug, Fail)]
+#[derive(Debug, Error)]
 pub enum GraphError {
     /// The graph is missing relevant information.
-    #[fail(display = "incomplete graph: {}", value)]
+    #[error("incomplete graph: {value}")]
     IncompleteGraph { value: String },
+
+    /// A token feature does not contain a valid head.
+    #[error("invalid head feature: {value}")]
+    InvalidHeadFeature { value: String },
+
+    /// A token already has the feature that would store its head.
+    #[error("head feature is already present: {value}")]
+    HeadFeatureExists { value: String },
+
+    /// The graph cannot be derived by a transition system.
+    #[error("graph cannot be derived: {value}")]
+    NotDerivable { value: String },
+}
+
+/// Evaluation errors.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum EvalError {
+    /// The numbers of gold and system sentences differ.
+    #[error("number of sentences differs: {gold} gold, {system} system")]
+    SentenceCountMismatch { gold: usize, system: usize },
+
+    /// The gold and system text differs.
+    ///
+    /// The offset is the index of the first character that differs in
+    /// the concatenation of the forms, not counting whitespace.
+    #[error("text of gold and system differs at character {offset}")]
+    TextMismatch { offset: usize },
+
+    /// The numbers of gold and system tokens in a sentence differ.
+    #[error("number of tokens in sentence {sentence} differs: {gold} gold, {system} system")]
+    TokenCountMismatch {
+        sentence: usize,
+        gold: usize,
+        system: usize,
+    },
+}
+
+/// Query errors.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum QueryError {
+    /// The query is not well-formed.
+    ///
+    /// The position is the index of the character at which the error
+    /// was detected.
+    #[error("invalid query syntax at character {position}: {value}")]
+    Syntax { position: usize, value: String },
+
+    /// A constraint refers to a token field that does not exist.
+    ///
+    /// The position is the index of the character at which the field
+    /// starts.
+    #[error("unknown token field at character {position}: {value}")]
+    UnknownField { position: usize, value: String },
 }
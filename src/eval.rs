@@ -0,0 +1,463 @@
+//! Evaluation of dependency parses.
+//!
+//! This module compares system sentences to gold standard sentences.
+//! Sentences are aligned by their position, tokens are aligned by their
+//! index. The evaluation computes:
+//!
+//! * Unlabeled attachment score (UAS): the fraction of tokens with the
+//!   correct head.
+//! * Labeled attachment score (LAS): the fraction of tokens with the
+//!   correct head and relation.
+//! * Label score (LS): the fraction of tokens with the correct relation.
+//! * Precision, recall and F1 per dependency relation.
+//! * Part-of-speech and lemma accuracy.
+//!
+//! Attachment scores are computed on the non-projective dependency graph
+//! (`Sentence::dep_graph`). The `conll18` module provides an evaluation
+//! that aligns tokens that were produced by different tokenizers.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, EvalError};
+use crate::graph::{DepTriple, Sentence};
+use crate::token::Token;
+
+pub mod conll18;
+
+/// Evaluation scores of a dependency relation.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RelationScores {
+    /// The number of gold tokens with the relation.
+    pub gold: usize,
+
+    /// The number of system tokens with the relation.
+    pub system: usize,
+
+    /// The number of system tokens with the relation and the correct
+    /// head and relation.
+    pub correct: usize,
+
+    /// Precision.
+    pub precision: f64,
+
+    /// Recall.
+    pub recall: f64,
+
+    /// F1 score, the harmonic mean of precision and recall.
+    pub f1: f64,
+}
+
+impl RelationScores {
+    fn new(gold: usize, system: usize, correct: usize) -> Self {
+        let precision = ratio(correct, system);
+        let recall = ratio(correct, gold);
+        let f1 = if precision + recall == 0. {
+            0.
+        } else {
+            2. * precision * recall / (precision + recall)
+        };
+
+        RelationScores {
+            gold,
+            system,
+            correct,
+            precision,
+            recall,
+            f1,
+        }
+    }
+}
+
+/// Evaluation report.
+///
+/// Scores are fractions in the range *[0, 1]*.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct EvalReport {
+    /// The number of evaluated sentences.
+    pub sentences: usize,
+
+    /// The number of evaluated tokens.
+    pub tokens: usize,
+
+    /// The number of tokens that were used for attachment scores.
+    ///
+    /// This excludes punctuation when punctuation is excluded and gold
+    /// tokens that do not have a head.
+    pub attachment_tokens: usize,
+
+    /// Unlabeled attachment score.
+    pub uas: f64,
+
+    /// Labeled attachment score.
+    pub las: f64,
+
+    /// Label score.
+    pub ls: f64,
+
+    /// Coarse-grained part-of-speech tag accuracy.
+    pub cpos_accuracy: f64,
+
+    /// Fine-grained part-of-speech tag accuracy.
+    pub pos_accuracy: f64,
+
+    /// Lemma accuracy.
+    pub lemma_accuracy: f64,
+
+    /// Scores per dependency relation.
+    pub relations: BTreeMap<String, RelationScores>,
+}
+
+/// Evaluator for dependency parses.
+///
+/// ```
+/// use conllx::eval::Evaluator;
+/// use conllx::graph::{DepTriple, Sentence};
+/// use conllx::token::Token;
+///
+/// let mut gold = Sentence::new();
+/// gold.push(Token::new("Hello"));
+/// gold.push(Token::new("world"));
+/// gold.dep_graph_mut().add_deprel(DepTriple::new(0, Some("ROOT"), 1));
+/// gold.dep_graph_mut().add_deprel(DepTriple::new(1, Some("OBJ"), 2));
+///
+/// let mut system = gold.clone();
+/// system.dep_graph_mut().add_deprel(DepTriple::new(1, Some("SUBJ"), 2));
+///
+/// let report = Evaluator::new().evaluate(&[gold], &[system]).unwrap();
+/// assert_eq!(report.uas, 1.0);
+/// assert_eq!(report.las, 0.5);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Evaluator {
+    exclude_punctuation: bool,
+}
+
+impl Evaluator {
+    /// Construct an evaluator.
+    ///
+    /// By default, punctuation is included in the attachment scores.
+    pub fn new() -> Self {
+        Evaluator {
+            exclude_punctuation: false,
+        }
+    }
+
+    /// Exclude punctuation from attachment scores and relation scores.
+    ///
+    /// A token is considered to be punctuation if its gold form does not
+    /// contain alphanumeric characters. Part-of-speech and lemma accuracy
+    /// are always computed over all tokens.
+    pub fn exclude_punctuation(mut self, exclude_punctuation: bool) -> Self {
+        self.exclude_punctuation = exclude_punctuation;
+        self
+    }
+
+    /// Evaluate system sentences against gold standard sentences.
+    ///
+    /// Returns an error when the number of sentences differs, or when
+    /// the number of tokens of a gold and system sentence differs.
+    pub fn evaluate<'a, G, S>(&self, gold: G, system: S) -> Result<EvalReport, Error>
+    where
+        G: IntoIterator<Item = &'a Sentence>,
+        S: IntoIterator<Item = &'a Sentence>,
+    {
+        let mut counts = Counts::default();
+
+        let mut gold = gold.into_iter();
+        let mut system = system.into_iter();
+        loop {
+            match (gold.next(), system.next()) {
+                (Some(gold_sentence), Some(system_sentence)) => {
+                    self.count_sentence(&mut counts, gold_sentence, system_sentence)?
+                }
+                (None, None) => break,
+                (gold_sentence, system_sentence) => {
+                    let n_gold = counts.sentences + gold_sentence.iter().count() + gold.count();
+                    let n_system =
+                        counts.sentences + system_sentence.iter().count() + system.count();
+                    return Err(EvalError::SentenceCountMismatch {
+                        gold: n_gold,
+                        system: n_system,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(counts.into())
+    }
+
+    fn count_sentence(
+        &self,
+        counts: &mut Counts,
+        gold: &Sentence,
+        system: &Sentence,
+    ) -> Result<(), Error> {
+        counts.sentences += 1;
+
+        if gold.len() != system.len() {
+            return Err(EvalError::TokenCountMismatch {
+                sentence: counts.sentences,
+                gold: gold.len() - 1,
+                system: system.len() - 1,
+            }
+            .into());
+        }
+
+        let gold_graph = gold.dep_graph();
+        let system_graph = system.dep_graph();
+
+        for i in 1..gold.len() {
+            let gold_token = token(gold, i);
+            let system_token = token(system, i);
+
+            counts.tokens += 1;
+            counts.cpos_correct += (gold_token.cpos() == system_token.cpos()) as usize;
+            counts.pos_correct += (gold_token.pos() == system_token.pos()) as usize;
+            counts.lemma_correct += (gold_token.lemma() == system_token.lemma()) as usize;
+
+            if self.exclude_punctuation && is_punctuation(gold_token.form()) {
+                continue;
+            }
+
+            let gold_triple = match gold_graph.head(i) {
+                Some(triple) => triple,
+                None => continue,
+            };
+            let system_triple = system_graph.head(i);
+
+            counts.count_attachment(&gold_triple, system_triple.as_ref());
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts for computing evaluation scores.
+#[derive(Default)]
+struct Counts {
+    sentences: usize,
+    tokens: usize,
+    attachment_tokens: usize,
+    head_correct: usize,
+    label_correct: usize,
+    head_label_correct: usize,
+    cpos_correct: usize,
+    pos_correct: usize,
+    lemma_correct: usize,
+
+    /// Gold, system and correct counts per relation.
+    relations: BTreeMap<String, (usize, usize, usize)>,
+}
+
+impl Counts {
+    fn count_attachment(&mut self, gold: &DepTriple<&str>, system: Option<&DepTriple<&str>>) {
+        self.attachment_tokens += 1;
+
+        let head_correct = system.map(|s| s.head() == gold.head()).unwrap_or(false);
+        let label_correct = system
+            .map(|s| s.relation() == gold.relation())
+            .unwrap_or(false);
+
+        self.head_correct += head_correct as usize;
+        self.label_correct += label_correct as usize;
+        self.head_label_correct += (head_correct && label_correct) as usize;
+
+        if let Some(relation) = gold.relation() {
+            self.relations.entry(relation.to_owned()).or_default().0 += 1;
+        }
+
+        if let Some(relation) = system.and_then(DepTriple::relation) {
+            let relation_counts = self.relations.entry(relation.to_owned()).or_default();
+            relation_counts.1 += 1;
+            relation_counts.2 += (head_correct && label_correct) as usize;
+        }
+    }
+}
+
+impl From<Counts> for EvalReport {
+    fn from(counts: Counts) -> Self {
+        EvalReport {
+            sentences: counts.sentences,
+            tokens: counts.tokens,
+            attachment_tokens: counts.attachment_tokens,
+            uas: ratio(counts.head_correct, counts.attachment_tokens),
+            las: ratio(counts.head_label_correct, counts.attachment_tokens),
+            ls: ratio(counts.label_correct, counts.attachment_tokens),
+            cpos_accuracy: ratio(counts.cpos_correct, counts.tokens),
+            pos_accuracy: ratio(counts.pos_correct, counts.tokens),
+            lemma_accuracy: ratio(counts.lemma_correct, counts.tokens),
+            relations: counts
+                .relations
+                .into_iter()
+                .map(|(relation, (gold, system, correct))| {
+                    (relation, RelationScores::new(gold, system, correct))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Check whether a form is punctuation.
+///
+/// A form is punctuation if it does not contain alphanumeric characters.
+fn is_punctuation(form: &str) -> bool {
+    !form.chars().any(char::is_alphanumeric)
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+fn token(sentence: &Sentence, idx: usize) -> &Token {
+    // Only the root node does not have a token.
+    sentence[idx].token().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use lazy_static::lazy_static;
+
+    use super::{EvalReport, Evaluator, RelationScores};
+    use crate::error::{Error, EvalError};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::read_sentences;
+    use crate::token::TokenBuilder;
+
+    lazy_static! {
+        static ref GOLD: Sentence = sentence(
+            &[
+                ("Die", "die", "ART"),
+                ("Katze", "Katze", "NN"),
+                ("schläft", "schlafen", "VVFIN"),
+                (".", ".", "$."),
+            ],
+            &[(2, "det"), (3, "subj"), (0, "root"), (3, "punct")],
+        );
+        static ref SYSTEM: Sentence = sentence(
+            &[
+                ("Die", "die", "ART"),
+                ("Katze", "Katze", "NE"),
+                ("schläft", "schläft", "VVFIN"),
+                (".", ".", "$."),
+            ],
+            &[(2, "det"), (3, "obj"), (0, "root"), (2, "punct")],
+        );
+    }
+
+    fn sentence(tokens: &[(&str, &str, &str)], heads: &[(usize, &str)]) -> Sentence {
+        let mut sentence = Sentence::new();
+        for &(form, lemma, pos) in tokens {
+            sentence.push(
+                TokenBuilder::new(form)
+                    .lemma(lemma)
+                    .cpos(pos)
+                    .pos(pos)
+                    .into(),
+            );
+        }
+
+        for (dependent, &(head, relation)) in heads.iter().enumerate() {
+            sentence.dep_graph_mut().add_deprel(DepTriple::new(
+                head,
+                Some(relation),
+                dependent + 1,
+            ));
+        }
+
+        sentence
+    }
+
+    #[test]
+    fn evaluate() {
+        let report = Evaluator::new()
+            .evaluate(slice::from_ref(&*GOLD), slice::from_ref(&*SYSTEM))
+            .unwrap();
+
+        assert_eq!(report.sentences, 1);
+        assert_eq!(report.tokens, 4);
+        assert_eq!(report.attachment_tokens, 4);
+        assert_eq!(report.uas, 0.75);
+        assert_eq!(report.las, 0.5);
+        assert_eq!(report.ls, 0.75);
+        assert_eq!(report.cpos_accuracy, 0.75);
+        assert_eq!(report.pos_accuracy, 0.75);
+        assert_eq!(report.lemma_accuracy, 0.75);
+
+        assert_eq!(report.relations["det"], RelationScores::new(1, 1, 1));
+        assert_eq!(report.relations["det"].f1, 1.);
+        assert_eq!(report.relations["subj"], RelationScores::new(1, 0, 0));
+        assert_eq!(report.relations["obj"], RelationScores::new(0, 1, 0));
+        assert_eq!(report.relations["punct"].f1, 0.);
+    }
+
+    #[test]
+    fn evaluate_without_punctuation() {
+        let report = Evaluator::new()
+            .exclude_punctuation(true)
+            .evaluate(slice::from_ref(&*GOLD), slice::from_ref(&*SYSTEM))
+            .unwrap();
+
+        assert_eq!(report.tokens, 4);
+        assert_eq!(report.attachment_tokens, 3);
+        assert_eq!(report.uas, 1.);
+        assert_eq!(report.las, 2. / 3.);
+        assert_eq!(report.ls, 2. / 3.);
+        assert_eq!(report.pos_accuracy, 0.75);
+        assert!(!report.relations.contains_key("punct"));
+    }
+
+    #[test]
+    fn evaluate_identical() {
+        let sentences = read_sentences("testdata/nonprojective.conll");
+        let report = Evaluator::new().evaluate(&sentences, &sentences).unwrap();
+        assert_eq!(report.sentences, sentences.len());
+        assert_eq!(report.las, 1.);
+        assert!(report.relations.values().all(|scores| scores.f1 == 1.));
+    }
+
+    #[test]
+    fn evaluate_empty() {
+        let report = Evaluator::new().evaluate(&[], &[]).unwrap();
+        assert_eq!(report, EvalReport::default());
+    }
+
+    #[test]
+    fn rejects_sentence_count_mismatch() {
+        match Evaluator::new().evaluate(&[GOLD.clone(), GOLD.clone()], slice::from_ref(&*SYSTEM)) {
+            Err(Error::Eval(err)) => {
+                assert_eq!(err, EvalError::SentenceCountMismatch { gold: 2, system: 1 })
+            }
+            _ => panic!("expected an evaluation error"),
+        }
+    }
+
+    #[test]
+    fn rejects_token_count_mismatch() {
+        let mut system = SYSTEM.clone();
+        system.push(TokenBuilder::new("!").into());
+
+        match Evaluator::new().evaluate(slice::from_ref(&*GOLD), &[system]) {
+            Err(Error::Eval(err)) => assert_eq!(
+                err,
+                EvalError::TokenCountMismatch {
+                    sentence: 1,
+                    gold: 4,
+                    system: 5
+                }
+            ),
+            _ => panic!("expected an evaluation error"),
+        }
+    }
+}
@@ -0,0 +1,781 @@
+//! CoNLL 2018 shared task evaluation.
+//!
+//! This module implements the evaluation of the CoNLL 2018 shared task
+//! on multilingual parsing from raw text to Universal Dependencies.
+//! Since the system sentences may be produced by a different tokenizer
+//! than the gold standard sentences, tokens and words are aligned using
+//! the character offsets in the concatenation of all forms (without
+//! whitespace):
+//!
+//! * Tokens and sentences are correct when their character spans match.
+//! * Words that are not part of a multiword token are aligned when their
+//!   character spans match. Words within overlapping multiword tokens
+//!   are aligned using the longest common subsequence of their
+//!   (lowercased) forms.
+//!
+//! Dependency scores are computed over the aligned words. Relations are
+//! compared on their universal part (`obl` for `obl:arg`). The
+//! following dependency scores are computed:
+//!
+//! * UAS: the word has the correct head.
+//! * LAS: the word has the correct head and relation.
+//! * MLAS: for content words, the correct head and relation, universal
+//!   part-of-speech tag (`Token::cpos`), universal features, and the
+//!   correct functional dependents. Like the CoNLL 2018 evaluation
+//!   script, functional dependents are compared by their relations,
+//!   universal part-of-speech tags and features only.
+//! * BLEX: for content words, the correct head, relation and lemma.
+//!
+//! All forms of the gold and system sentences must add up to the same
+//! text.
+
+use std::cmp::{max, Ordering};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, EvalError};
+use crate::graph::Sentence;
+use crate::token::Token;
+
+/// Relations of content words.
+static CONTENT_RELATIONS: &[&str] = &[
+    "acl",
+    "advcl",
+    "advmod",
+    "amod",
+    "appos",
+    "ccomp",
+    "compound",
+    "conj",
+    "csubj",
+    "dep",
+    "discourse",
+    "dislocated",
+    "expl",
+    "fixed",
+    "flat",
+    "goeswith",
+    "iobj",
+    "list",
+    "nmod",
+    "nsubj",
+    "nummod",
+    "obj",
+    "obl",
+    "orphan",
+    "parataxis",
+    "reparandum",
+    "root",
+    "vocative",
+    "xcomp",
+];
+
+/// Relations of function words.
+static FUNCTIONAL_RELATIONS: &[&str] = &["aux", "case", "cc", "clf", "cop", "det", "mark"];
+
+/// Universal features that are used in the MLAS score.
+static UNIVERSAL_FEATURES: &[&str] = &[
+    "Abbr", "Animacy", "Aspect", "Case", "Definite", "Degree", "Evident", "Foreign", "Gender",
+    "Mood", "NumType", "Number", "Person", "Polarity", "Polite", "Poss", "PronType", "Reflex",
+    "Tense", "VerbForm", "Voice",
+];
+
+/// Precision, recall and F1 score.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Score {
+    /// The number of gold units.
+    pub gold: usize,
+
+    /// The number of system units.
+    pub system: usize,
+
+    /// The number of correct system units.
+    pub correct: usize,
+
+    /// The number of aligned units.
+    pub aligned: usize,
+
+    /// Precision.
+    pub precision: f64,
+
+    /// Recall.
+    pub recall: f64,
+
+    /// F1 score, the harmonic mean of precision and recall.
+    pub f1: f64,
+
+    /// Accuracy on the aligned units.
+    pub aligned_accuracy: f64,
+}
+
+impl Score {
+    fn new(gold: usize, system: usize, correct: usize, aligned: usize) -> Self {
+        Score {
+            gold,
+            system,
+            correct,
+            aligned,
+            precision: ratio(correct, system),
+            recall: ratio(correct, gold),
+            f1: ratio(2 * correct, gold + system),
+            aligned_accuracy: ratio(correct, aligned),
+        }
+    }
+}
+
+/// CoNLL 2018 shared task evaluation report.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Conll18Report {
+    /// Token scores.
+    pub tokens: Score,
+
+    /// Sentence scores.
+    pub sentences: Score,
+
+    /// Word scores.
+    pub words: Score,
+
+    /// Unlabeled attachment scores.
+    pub uas: Score,
+
+    /// Labeled attachment scores.
+    pub las: Score,
+
+    /// Morphology-aware labeled attachment scores.
+    pub mlas: Score,
+
+    /// Bilexical dependency scores.
+    pub blex: Score,
+}
+
+/// Evaluate system sentences against gold standard sentences.
+///
+/// The gold and system sentences are aligned as described in the module
+/// documentation. An error is returned when the text of the gold and
+/// system sentences differs.
+pub fn evaluate<'a, G, S>(gold: G, system: S) -> Result<Conll18Report, Error>
+where
+    G: IntoIterator<Item = &'a Sentence>,
+    S: IntoIterator<Item = &'a Sentence>,
+{
+    let gold = Document::new(gold);
+    let system = Document::new(system);
+
+    if let Some(offset) = gold
+        .characters
+        .iter()
+        .zip(&system.characters)
+        .position(|(g, s)| g != s)
+        .or_else(|| {
+            if gold.characters.len() != system.characters.len() {
+                Some(gold.characters.len().min(system.characters.len()))
+            } else {
+                None
+            }
+        })
+    {
+        return Err(EvalError::TextMismatch { offset }.into());
+    }
+
+    let alignment = Alignment::new(&gold.words, &system.words);
+
+    Ok(Conll18Report {
+        tokens: spans_score(&gold.tokens, &system.tokens),
+        sentences: spans_score(&gold.sentences, &system.sentences),
+        words: Score::new(
+            gold.words.len(),
+            system.words.len(),
+            alignment.pairs.len(),
+            alignment.pairs.len(),
+        ),
+        uas: alignment.score(&gold, &system, |_| true, |aligned| aligned.heads_match()),
+        las: alignment.score(
+            &gold,
+            &system,
+            |_| true,
+            |aligned| aligned.heads_match() && aligned.relation_match(),
+        ),
+        mlas: alignment.score(
+            &gold,
+            &system,
+            |w| w.is_content(),
+            |aligned| {
+                aligned.heads_match()
+                    && aligned.relation_match()
+                    && aligned.morphology_match()
+                    && aligned.functional_dependents_match()
+            },
+        ),
+        blex: alignment.score(
+            &gold,
+            &system,
+            |w| w.is_content(),
+            |aligned| aligned.heads_match() && aligned.relation_match() && aligned.lemma_match(),
+        ),
+    })
+}
+
+/// Character span, the end is exclusive.
+type Span = (usize, usize);
+
+/// The head of a word.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Head {
+    /// The word does not have a head.
+    Missing,
+
+    /// The word is attached to the root.
+    Root,
+
+    /// The word is attached to the word with the given index.
+    Word(usize),
+}
+
+/// A syntactic word.
+struct Word<'a> {
+    token: &'a Token,
+    span: Span,
+    multiword: bool,
+    head: Head,
+    relation: Option<String>,
+    functional_dependents: Vec<usize>,
+}
+
+impl<'a> Word<'a> {
+    fn is_content(&self) -> bool {
+        self.relation
+            .as_ref()
+            .map(|relation| CONTENT_RELATIONS.contains(&relation.as_str()))
+            .unwrap_or(false)
+    }
+
+    fn is_functional(&self) -> bool {
+        self.relation
+            .as_ref()
+            .map(|relation| FUNCTIONAL_RELATIONS.contains(&relation.as_str()))
+            .unwrap_or(false)
+    }
+}
+
+/// A gold word paired with the aligned system word.
+#[derive(Clone, Copy)]
+struct AlignedWord<'a, 'b> {
+    gold: &'b Word<'a>,
+    gold_document: &'b Document<'a>,
+    system: &'b Word<'a>,
+    system_document: &'b Document<'a>,
+    alignment: &'b Alignment,
+}
+
+impl<'a, 'b> AlignedWord<'a, 'b> {
+    /// Map the head of a system word to the gold word it is aligned with.
+    fn aligned_head(&self, head: Head) -> Option<Head> {
+        match head {
+            Head::Word(idx) => self.alignment.system_to_gold[idx].map(Head::Word),
+            head => Some(head),
+        }
+    }
+
+    fn heads_match(&self) -> bool {
+        self.gold.head != Head::Missing
+            && Some(self.gold.head) == self.aligned_head(self.system.head)
+    }
+
+    fn relation_match(&self) -> bool {
+        self.gold.relation == self.system.relation
+    }
+
+    fn morphology_match(&self) -> bool {
+        morphology_match(self.gold, self.system)
+    }
+
+    /// Lemmas match when they are equal or the gold lemma is absent.
+    fn lemma_match(&self) -> bool {
+        match self.gold.token.lemma() {
+            Some("_") | None => true,
+            lemma => lemma == self.system.token.lemma(),
+        }
+    }
+
+    /// Functional dependents match when they have the same relations and
+    /// morphology. The alignment of the dependents is not considered.
+    fn functional_dependents_match(&self) -> bool {
+        let gold_dependents = &self.gold.functional_dependents;
+        let system_dependents = &self.system.functional_dependents;
+
+        gold_dependents.len() == system_dependents.len()
+            && gold_dependents
+                .iter()
+                .zip(system_dependents)
+                .all(|(&gold_idx, &system_idx)| {
+                    let gold = &self.gold_document.words[gold_idx];
+                    let system = &self.system_document.words[system_idx];
+
+                    gold.relation == system.relation && morphology_match(gold, system)
+                })
+    }
+}
+
+/// Sentences in the representation used for evaluation.
+struct Document<'a> {
+    characters: Vec<char>,
+    tokens: Vec<Span>,
+    sentences: Vec<Span>,
+    words: Vec<Word<'a>>,
+}
+
+impl<'a> Document<'a> {
+    fn new(sentences: impl IntoIterator<Item = &'a Sentence>) -> Self {
+        let mut document = Document {
+            characters: Vec::new(),
+            tokens: Vec::new(),
+            sentences: Vec::new(),
+            words: Vec::new(),
+        };
+
+        for sentence in sentences {
+            document.add_sentence(sentence);
+        }
+
+        document
+    }
+
+    fn add_sentence(&mut self, sentence: &'a Sentence) {
+        if sentence.len() == 1 {
+            return;
+        }
+
+        let sentence_start = self.characters.len();
+        let word_offset = self.words.len();
+
+        let mut idx = 1;
+        while idx < sentence.len() {
+            let (form, last) = match sentence.multiword_token(idx) {
+                Some(multiword_token) => (multiword_token.form(), multiword_token.last()),
+                None => (token(sentence, idx).form(), idx),
+            };
+
+            let start = self.characters.len();
+            self.characters
+                .extend(form.chars().filter(|c| !c.is_whitespace()));
+            let span = (start, self.characters.len());
+            self.tokens.push(span);
+
+            for word_idx in idx..=last {
+                let head = match sentence.dep_graph().head(word_idx) {
+                    Some(triple) if triple.head() == 0 => Head::Root,
+                    Some(triple) => Head::Word(word_offset + triple.head() - 1),
+                    None => Head::Missing,
+                };
+
+                self.words.push(Word {
+                    token: token(sentence, word_idx),
+                    span,
+                    multiword: last != idx,
+                    head,
+                    relation: sentence
+                        .dep_graph()
+                        .head(word_idx)
+                        .and_then(|triple| triple.relation().map(universal_relation)),
+                    functional_dependents: Vec::new(),
+                });
+            }
+
+            idx = last + 1;
+        }
+
+        // Collect the functional dependents of each word.
+        for idx in word_offset..self.words.len() {
+            if let Head::Word(head) = self.words[idx].head {
+                if self.words[idx].is_functional() {
+                    self.words[head].functional_dependents.push(idx);
+                }
+            }
+        }
+
+        self.sentences.push((sentence_start, self.characters.len()));
+    }
+}
+
+/// Alignment of gold and system words.
+struct Alignment {
+    /// Pairs of aligned gold and system word indices.
+    pairs: Vec<(usize, usize)>,
+
+    /// The gold word that each system word is aligned with.
+    system_to_gold: Vec<Option<usize>>,
+}
+
+impl Alignment {
+    fn new(gold: &[Word], system: &[Word]) -> Self {
+        let mut alignment = Alignment {
+            pairs: Vec::new(),
+            system_to_gold: vec![None; system.len()],
+        };
+
+        let (mut gi, mut si) = (0, 0);
+        while gi < gold.len() && si < system.len() {
+            if gold[gi].multiword || system[si].multiword {
+                let (gs, ss, ge, se) = multiword_span(gold, system, gi, si);
+                alignment.align_lcs(gold, system, gs..ge, ss..se);
+                gi = ge;
+                si = se;
+            } else {
+                match gold[gi].span.0.cmp(&system[si].span.0) {
+                    Ordering::Equal if gold[gi].span.1 == system[si].span.1 => {
+                        alignment.align(gi, si);
+                        gi += 1;
+                        si += 1;
+                    }
+                    Ordering::Less | Ordering::Equal => gi += 1,
+                    Ordering::Greater => si += 1,
+                }
+            }
+        }
+
+        alignment
+    }
+
+    fn align(&mut self, gold: usize, system: usize) {
+        self.pairs.push((gold, system));
+        self.system_to_gold[system] = Some(gold);
+    }
+
+    /// Align words using the longest common subsequence of their forms.
+    fn align_lcs(
+        &mut self,
+        gold: &[Word],
+        system: &[Word],
+        gold_range: std::ops::Range<usize>,
+        system_range: std::ops::Range<usize>,
+    ) {
+        let gold_forms = gold[gold_range.clone()]
+            .iter()
+            .map(|w| w.token.form().to_lowercase())
+            .collect::<Vec<_>>();
+        let system_forms = system[system_range.clone()]
+            .iter()
+            .map(|w| w.token.form().to_lowercase())
+            .collect::<Vec<_>>();
+
+        // lcs[g][s] is the length of the longest common subsequence of
+        // gold_forms[g..] and system_forms[s..].
+        let mut lcs = vec![vec![0; system_forms.len() + 1]; gold_forms.len() + 1];
+        for g in (0..gold_forms.len()).rev() {
+            for s in (0..system_forms.len()).rev() {
+                lcs[g][s] = if gold_forms[g] == system_forms[s] {
+                    1 + lcs[g + 1][s + 1]
+                } else {
+                    max(lcs[g + 1][s], lcs[g][s + 1])
+                };
+            }
+        }
+
+        let (mut g, mut s) = (0, 0);
+        while g < gold_forms.len() && s < system_forms.len() {
+            if gold_forms[g] == system_forms[s] {
+                self.align(gold_range.start + g, system_range.start + s);
+                g += 1;
+                s += 1;
+            } else if lcs[g][s] == lcs[g + 1][s] {
+                g += 1;
+            } else {
+                s += 1;
+            }
+        }
+    }
+
+    /// Compute the score of aligned words.
+    ///
+    /// Only words for which `filter` holds are counted. For aligned
+    /// words, the filter is applied to the gold word.
+    fn score<'a>(
+        &self,
+        gold: &Document<'a>,
+        system: &Document<'a>,
+        filter: impl Fn(&Word) -> bool,
+        correct: impl Fn(&AlignedWord) -> bool,
+    ) -> Score {
+        let n_gold = gold.words.iter().filter(|w| filter(w)).count();
+        let n_system = system.words.iter().filter(|w| filter(w)).count();
+
+        let mut n_aligned = 0;
+        let mut n_correct = 0;
+        for &(gold_idx, system_idx) in &self.pairs {
+            if !filter(&gold.words[gold_idx]) {
+                continue;
+            }
+
+            n_aligned += 1;
+
+            let aligned = AlignedWord {
+                gold: &gold.words[gold_idx],
+                gold_document: gold,
+                system: &system.words[system_idx],
+                system_document: system,
+                alignment: self,
+            };
+
+            if correct(&aligned) {
+                n_correct += 1;
+            }
+        }
+
+        Score::new(n_gold, n_system, n_correct, n_aligned)
+    }
+}
+
+/// Find the smallest span of words that covers overlapping multiword
+/// tokens, starting at `gi` and `si`.
+///
+/// Returns the start and (exclusive) end indices of the gold and system
+/// words in the span.
+fn multiword_span(
+    gold: &[Word],
+    system: &[Word],
+    mut gi: usize,
+    mut si: usize,
+) -> (usize, usize, usize, usize) {
+    let mut span_end;
+    if gold[gi].multiword {
+        span_end = gold[gi].span.1;
+        if !system[si].multiword && system[si].span.0 < gold[gi].span.0 {
+            si += 1;
+        }
+    } else {
+        span_end = system[si].span.1;
+        if !gold[gi].multiword && gold[gi].span.0 < system[si].span.0 {
+            gi += 1;
+        }
+    }
+
+    let (gs, ss) = (gi, si);
+
+    while !beyond_end(gold, gi, span_end) || !beyond_end(system, si, span_end) {
+        if gi < gold.len() && (si >= system.len() || gold[gi].span.0 <= system[si].span.0) {
+            span_end = extend_end(&gold[gi], span_end);
+            gi += 1;
+        } else {
+            span_end = extend_end(&system[si], span_end);
+            si += 1;
+        }
+    }
+
+    (gs, ss, gi, si)
+}
+
+fn beyond_end(words: &[Word], idx: usize, span_end: usize) -> bool {
+    match words.get(idx) {
+        None => true,
+        Some(word) if word.multiword => word.span.0 >= span_end,
+        Some(word) => word.span.1 > span_end,
+    }
+}
+
+fn extend_end(word: &Word, span_end: usize) -> usize {
+    if word.multiword {
+        max(word.span.1, span_end)
+    } else {
+        span_end
+    }
+}
+
+/// Check whether the universal part-of-speech tags and universal features
+/// of two words match.
+fn morphology_match(gold: &Word, system: &Word) -> bool {
+    gold.token.cpos() == system.token.cpos()
+        && universal_features(gold) == universal_features(system)
+}
+
+/// Get the universal features of a word.
+fn universal_features<'a>(word: &Word<'a>) -> Vec<(&'a str, Option<&'a str>)> {
+    word.token
+        .features()
+        .map(|features| {
+            features
+                .iter()
+                .filter(|(feature, _)| UNIVERSAL_FEATURES.contains(&feature.as_str()))
+                .map(|(feature, value)| (feature.as_str(), value.as_deref()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Count the matching spans of sorted gold and system spans.
+fn spans_score(gold: &[Span], system: &[Span]) -> Score {
+    let mut correct = 0;
+    let (mut gi, mut si) = (0, 0);
+    while gi < gold.len() && si < system.len() {
+        match system[si].0.cmp(&gold[gi].0) {
+            Ordering::Less => si += 1,
+            Ordering::Greater => gi += 1,
+            Ordering::Equal => {
+                if gold[gi].1 == system[si].1 {
+                    correct += 1;
+                }
+                gi += 1;
+                si += 1;
+            }
+        }
+    }
+
+    Score::new(gold.len(), system.len(), correct, correct)
+}
+
+/// Get the universal part of a relation.
+fn universal_relation(relation: &str) -> String {
+    relation.split(':').next().unwrap_or(relation).to_owned()
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+fn token(sentence: &Sentence, idx: usize) -> &Token {
+    // Only the root node does not have a token.
+    sentence[idx].token().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{BufReader, Cursor};
+
+    use super::{evaluate, Score};
+    use crate::conllu::Reader;
+    use crate::error::{Error, EvalError};
+    use crate::graph::Sentence;
+    use crate::io::ReadSentence;
+
+    static BASIC: &str = "testdata/basic.conllu";
+
+    static MULTIWORD: &str = "testdata/multiword.conllu";
+
+    fn read_file(filename: &str) -> Vec<Sentence> {
+        Reader::new(BufReader::new(File::open(filename).unwrap()))
+            .sentences()
+            .map(|s| s.unwrap())
+            .collect()
+    }
+
+    fn read_str(data: &str) -> Vec<Sentence> {
+        Reader::new(Cursor::new(data))
+            .sentences()
+            .map(|s| s.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn identical() {
+        for filename in &[BASIC, MULTIWORD] {
+            let sentences = read_file(filename);
+            let report = evaluate(&sentences, &sentences).unwrap();
+            for score in &[
+                report.tokens,
+                report.sentences,
+                report.words,
+                report.uas,
+                report.las,
+                report.mlas,
+                report.blex,
+            ] {
+                assert_eq!(score.f1, 1.);
+            }
+        }
+    }
+
+    #[test]
+    fn word_alignment() {
+        let gold = read_file(MULTIWORD);
+        let system = read_str(
+            "1\tIch\tich\tPRON\tPPER\t_\t2\tobj\t_\t_
+2\tgehe\tgehen\tVERB\tVVFIN\t_\t0\troot\t_\t_
+3\tzum\tzu\tADP\tAPPRART\t_\t4\tcase\t_\t_
+4\tBahnhof\tBahnhof\tNOUN\tNN\t_\t2\tobl:arg\t_\t_
+5-6\tim\t_\t_\t_\t_\t_\t_\t_\t_
+5\tin\tin\tADP\tAPPR\t_\t7\tcase\t_\t_
+6\tdem\tder\tDET\tART\t_\t7\tdet\t_\t_
+7\tZug\tZug\tNOUN\tNN\t_\t2\tobl\t_\t_
+",
+        );
+
+        let report = evaluate(&gold, &system).unwrap();
+        assert_eq!(report.tokens, Score::new(6, 6, 6, 6));
+        assert_eq!(report.sentences, Score::new(1, 1, 1, 1));
+
+        // The words of "zum" cannot be aligned.
+        assert_eq!(report.words, Score::new(8, 7, 6, 6));
+        assert_eq!(report.uas, Score::new(8, 7, 6, 6));
+        assert_eq!(report.uas.f1, 0.8);
+
+        // Relations are compared on their universal part.
+        assert_eq!(report.las, Score::new(8, 7, 5, 6));
+
+        // "Bahnhof" has the wrong functional dependents.
+        assert_eq!(report.mlas, Score::new(4, 4, 2, 4));
+        assert_eq!(report.blex, Score::new(4, 4, 3, 4));
+    }
+
+    #[test]
+    fn misaligned_functional_dependent() {
+        let gold = read_str(
+            "1\tder\tder\tDET\tART\t_\t3\tdet\t_\t_
+2\tdie\tder\tDET\tART\t_\t3\tdep\t_\t_
+3\tHund\tHund\tNOUN\tNN\t_\t0\troot\t_\t_
+",
+        );
+        let system = read_str(
+            "1\tder\tder\tDET\tART\t_\t3\tdep\t_\t_
+2\tdie\tder\tDET\tART\t_\t3\tdet\t_\t_
+3\tHund\tHund\tNOUN\tNN\t_\t0\troot\t_\t_
+",
+        );
+
+        // The determiner of "Hund" is a different word, but has the same
+        // relation and morphology.
+        let report = evaluate(&gold, &system).unwrap();
+        assert_eq!(report.mlas, Score::new(2, 2, 1, 2));
+    }
+
+    #[test]
+    fn token_alignment() {
+        let gold = read_file(BASIC);
+        let system = read_str(
+            "1\tDie\tder\tDET\tART\t_\t2\tdet\t_\t_
+2\tGroß\tgroß\tADJ\tADJA\t_\t3\tamod\t_\t_
+3\taufnahme\tAufnahme\tNOUN\tNN\t_\t0\troot\t_\t_
+4\t.\t.\tPUNCT\t$.\t_\t3\tpunct\t_\t_
+5\tGilles\tGilles\tPROPN\tNE\t_\t3\tparataxis\t_\t_
+6\tDeleuze\tDeleuze\tPROPN\tNE\t_\t5\tflat\t_\t_
+",
+        );
+
+        let report = evaluate(&gold, &system).unwrap();
+        assert_eq!(report.tokens, Score::new(5, 6, 4, 4));
+        assert_eq!(report.sentences, Score::new(2, 1, 0, 0));
+        assert_eq!(report.words, Score::new(5, 6, 4, 4));
+
+        // "Deleuze" is attached correctly, "Gilles" is not attached to
+        // the root, the heads of "Die" and "." are not aligned.
+        assert_eq!(report.uas.correct, 1);
+    }
+
+    #[test]
+    fn rejects_text_mismatch() {
+        let gold = read_file(MULTIWORD);
+        let system =
+            read_str("1\tIch\t_\t_\t_\t_\t0\troot\t_\t_\n2\tging\t_\t_\t_\t_\t1\tdep\t_\t_\n");
+
+        match evaluate(&gold, &system) {
+            Err(Error::Eval(err)) => assert_eq!(err, EvalError::TextMismatch { offset: 4 }),
+            _ => panic!("expected an evaluation error"),
+        }
+    }
+}
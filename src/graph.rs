@@ -1,15 +1,18 @@
 //! Dependency graphs.
 
 use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter};
 use std::iter::FromIterator;
-use std::ops::{Index, IndexMut};
+use std::mem;
+use std::ops::{Index, IndexMut, RangeInclusive};
 
 use petgraph::graph::{node_index, DiGraph, NodeIndices, NodeWeightsMut};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 
-use crate::token::Token;
+use crate::token::{Misc, Token};
 
 /// Dependency graph node.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -19,33 +22,114 @@ pub enum Node {
 
     /// Token node.
     Token(Token),
+
+    /// Empty node.
+    ///
+    /// Empty nodes are not part of the dependency graph. They are only
+    /// used in enhanced dependency graphs, for instance to represent
+    /// elided predicates.
+    Empty(Token),
 }
 
 impl Node {
     pub fn is_root(&self) -> bool {
-        !self.is_token()
+        match self {
+            Node::Root => true,
+            Node::Token(_) | Node::Empty(_) => false,
+        }
     }
 
     pub fn is_token(&self) -> bool {
         match self {
-            Node::Root => false,
             Node::Token(_) => true,
+            Node::Root | Node::Empty(_) => false,
+        }
+    }
+
+    pub fn is_empty_node(&self) -> bool {
+        match self {
+            Node::Empty(_) => true,
+            Node::Root | Node::Token(_) => false,
         }
     }
 
+    /// Get the token of a token or empty node.
     pub fn token(&self) -> Option<&Token> {
         match self {
             Node::Root => None,
-            Node::Token(token) => Some(token),
+            Node::Token(token) | Node::Empty(token) => Some(token),
         }
     }
 
+    /// Get the token of a token or empty node mutably.
     pub fn token_mut(&mut self) -> Option<&mut Token> {
         match self {
             Node::Root => None,
-            Node::Token(token) => Some(token),
+            Node::Token(token) | Node::Empty(token) => Some(token),
+        }
+    }
+}
+
+/// Node identifier.
+///
+/// A node identifier identifies the root or a token by its index
+/// (`NodeId::Token`), or an empty node (`NodeId::Empty`). Empty nodes
+/// are identified by the index of the token that they follow and their
+/// position among the empty nodes that follow that token. For example,
+/// the CoNLL-U identifier `8.1` corresponds to `NodeId::Empty(8, 1)`.
+/// Positions of empty nodes start at 1.
+///
+/// Node identifiers are ordered by their position in the sentence.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NodeId {
+    /// Index of the root (0) or a token.
+    Token(usize),
+
+    /// Index of the preceding token and position of an empty node.
+    Empty(usize, usize),
+}
+
+impl NodeId {
+    /// Get the index of the token, or the preceding token of an empty node.
+    pub fn token(&self) -> usize {
+        match *self {
+            NodeId::Token(token) | NodeId::Empty(token, _) => token,
         }
     }
+
+    fn sort_key(&self) -> (usize, usize) {
+        match *self {
+            NodeId::Token(token) => (token, 0),
+            NodeId::Empty(token, position) => (token, position),
+        }
+    }
+}
+
+impl Display for NodeId {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            NodeId::Token(token) => write!(fmt, "{}", token),
+            NodeId::Empty(token, position) => write!(fmt, "{}.{}", token, position),
+        }
+    }
+}
+
+impl From<usize> for NodeId {
+    fn from(token: usize) -> Self {
+        NodeId::Token(token)
+    }
+}
+
+impl Ord for NodeId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for NodeId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// A dependency triple.
@@ -89,6 +173,139 @@ where
     }
 }
 
+/// An enhanced dependency triple.
+///
+/// An enhanced dependency triple consists of: a head identifier; a
+/// dependent identifier; and an optional dependency label. In contrast
+/// to `DepTriple`, the head and dependent can also be empty nodes.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct EnhancedDepTriple<S> {
+    head: NodeId,
+    dependent: NodeId,
+    relation: Option<S>,
+}
+
+impl<S> EnhancedDepTriple<S> {
+    /// Construct a new enhanced dependency triple.
+    pub fn new(head: impl Into<NodeId>, relation: Option<S>, dependent: impl Into<NodeId>) -> Self {
+        EnhancedDepTriple {
+            head: head.into(),
+            dependent: dependent.into(),
+            relation,
+        }
+    }
+
+    /// Get the dependent.
+    pub fn dependent(&self) -> NodeId {
+        self.dependent
+    }
+
+    /// Get the head.
+    pub fn head(&self) -> NodeId {
+        self.head
+    }
+}
+
+impl<S> EnhancedDepTriple<S>
+where
+    S: Borrow<str>,
+{
+    pub fn relation(&self) -> Option<&str> {
+        self.relation.as_ref().map(Borrow::borrow)
+    }
+}
+
+/// An enhanced dependency edge: (dependent, head, relation).
+///
+/// Edges are ordered by dependent, so that the heads of a dependent
+/// can be retrieved using a range query.
+type EnhancedEdge = (NodeId, NodeId, Option<String>);
+
+/// A multiword token.
+///
+/// A multiword token is a surface token that corresponds to multiple
+/// tokens in the dependency graph, such as German *zum* (*zu dem*).
+/// The tokens are identified by the inclusive range `first..=last`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultiwordToken {
+    first: usize,
+    last: usize,
+    form: String,
+    misc: Option<Misc>,
+}
+
+impl MultiwordToken {
+    /// Construct a multiword token for the tokens `first..=last`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range consists of less than two tokens.
+    pub fn new(first: usize, last: usize, form: impl Into<String>) -> Self {
+        assert!(
+            first < last,
+            "A multiword token should consist of at least two tokens"
+        );
+
+        MultiwordToken {
+            first,
+            last,
+            form: form.into(),
+            misc: None,
+        }
+    }
+
+    /// Check whether the multiword token covers the token at index `token`.
+    pub fn contains(&self, token: usize) -> bool {
+        token >= self.first && token <= self.last
+    }
+
+    /// Get the index of the first token that is covered.
+    pub fn first(&self) -> usize {
+        self.first
+    }
+
+    /// Get the surface form.
+    pub fn form(&self) -> &str {
+        &self.form
+    }
+
+    /// Get the index of the last token that is covered.
+    pub fn last(&self) -> usize {
+        self.last
+    }
+
+    /// Get the miscellaneous annotations of the multiword token.
+    pub fn misc(&self) -> Option<&Misc> {
+        self.misc.as_ref()
+    }
+
+    /// Get the miscellaneous annotations of the multiword token.
+    ///
+    /// Returns a mutable reference, so that the annotations can be updated.
+    pub fn misc_mut(&mut self) -> Option<&mut Misc> {
+        self.misc.as_mut()
+    }
+
+    /// Get the range of tokens that is covered.
+    pub fn range(&self) -> RangeInclusive<usize> {
+        self.first..=self.last
+    }
+
+    /// Set the surface form.
+    ///
+    /// Returns the form that is replaced.
+    pub fn set_form(&mut self, form: impl Into<String>) -> String {
+        mem::replace(&mut self.form, form.into())
+    }
+
+    /// Set the miscellaneous annotations of the multiword token.
+    ///
+    /// Returns the annotations that are replaced.
+    pub fn set_misc(&mut self, misc: Option<Misc>) -> Option<Misc> {
+        mem::replace(&mut self.misc, misc)
+    }
+}
+
 /// Relation projectivity.
 ///
 /// This enum is used in the underlying `petgraph` graph to distinguish
@@ -105,6 +322,29 @@ pub enum Projectivity {
 /// Dependency edge.
 pub type Edge = (Projectivity, Option<String>);
 
+/// Treatment of the dependents of a removed token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RemovalPolicy {
+    /// Attach the dependents to the head of the removed token, keeping
+    /// their relations. The dependents lose their heads if the removed
+    /// token does not have a head.
+    Reattach,
+
+    /// Remove the head relations of the dependents.
+    Detach,
+}
+
+/// Treatment of relations that cross the boundary of a split sentence.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SplitPolicy {
+    /// Attach the dependent to the root of its sentence, keeping the
+    /// relation.
+    AttachToRoot,
+
+    /// Remove the relation.
+    Detach,
+}
+
 /// A CoNLL-X dependency graph.
 ///
 /// `Sentence` stores a dependency graph. The nodes in the graph
@@ -116,8 +356,19 @@ pub type Edge = (Projectivity, Option<String>);
 /// `DiGraph` data structure that enforces variants such as
 /// single-headedness. The `into_inner`/`get_ref` methods can
 /// be used to unwrap or get a reference to the wrapped graph.
+///
+/// Besides the dependency graph, a sentence stores multiword tokens
+/// as a separate layer over the tokens. Empty nodes and the enhanced
+/// dependency graph are also stored outside the dependency graph.
+/// Finally, a sentence stores the comments that precede it.
 #[derive(Clone, Debug)]
-pub struct Sentence(DiGraph<Node, Edge>);
+pub struct Sentence {
+    comments: Vec<String>,
+    graph: DiGraph<Node, Edge>,
+    multiword_tokens: Vec<MultiwordToken>,
+    empty_nodes: BTreeMap<NodeId, Node>,
+    enhanced_edges: BTreeSet<EnhancedEdge>,
+}
 
 #[allow(clippy::len_without_is_empty)]
 impl Sentence {
@@ -137,30 +388,84 @@ impl Sentence {
     pub fn new() -> Self {
         let mut g = DiGraph::new();
         g.add_node(Node::Root);
-        Sentence(g)
+        Sentence {
+            comments: Vec::new(),
+            graph: g,
+            multiword_tokens: Vec::new(),
+            empty_nodes: BTreeMap::new(),
+            enhanced_edges: BTreeSet::new(),
+        }
+    }
+
+    /// Add a comment.
+    ///
+    /// The comment should not include the leading `#`.
+    pub fn add_comment(&mut self, comment: impl Into<String>) {
+        self.comments.push(comment.into());
+    }
+
+    /// Get the comments of the sentence, in order.
+    ///
+    /// Comments do not include the leading `#`.
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Get the comments of the sentence mutably.
+    pub fn comments_mut(&mut self) -> &mut Vec<String> {
+        &mut self.comments
+    }
+
+    /// Get the sentence metadata.
+    ///
+    /// Metadata are comments of the form `key = value`, such as
+    /// `sent_id = 1`. If a key occurs multiple times, the last value
+    /// is used.
+    pub fn metadata(&self) -> BTreeMap<&str, &str> {
+        self.comments
+            .iter()
+            .filter_map(|comment| parse_metadata(comment))
+            .collect()
+    }
+
+    /// Set a metadata value.
+    ///
+    /// This replaces the first `key = value` comment with the given key.
+    /// If there is no such comment, a comment is added.
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        let comment = format!("{} = {}", key, value);
+
+        match self
+            .comments
+            .iter_mut()
+            .find(|comment| parse_metadata(comment).map(|(k, _)| k) == Some(key))
+        {
+            Some(existing) => *existing = comment,
+            None => self.comments.push(comment),
+        }
     }
 
     /// Get a reference to the `DiGraph` of the sentence.
     pub fn get_ref(&self) -> &DiGraph<Node, Edge> {
-        &self.0
+        &self.graph
     }
 
     /// Unwrap the `DiGraph` of the sentence.
     pub fn into_inner(self) -> DiGraph<Node, Edge> {
-        self.0
+        self.graph
     }
 
     /// Get an iterator over the nodes in the graph.
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter {
-            inner: self.0.node_indices(),
-            graph: &self.0,
+            inner: self.graph.node_indices(),
+            graph: &self.graph,
         }
     }
 
     /// Get a mutable iterator over the nodes in the graph.
-    pub fn iter_mut(&mut self) -> IterMut {
-        IterMut(self.0.node_weights_mut())
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(self.graph.node_weights_mut())
     }
 
     /// Add a new token to the graph.
@@ -170,100 +475,686 @@ impl Sentence {
     /// Returns the index of the token. The first pushed token has index 1,
     /// since index 0 is reserved by the root of the graph.
     pub fn push(&mut self, token: Token) -> usize {
-        self.0.add_node(Node::Token(token)).index()
+        self.graph.add_node(Node::Token(token)).index()
+    }
+
+    /// Insert a token at index `index`.
+    ///
+    /// The tokens at `index` and later are shifted one position to the
+    /// right. Relations, multiword tokens, empty nodes and enhanced
+    /// relations are updated to use the new indices. A multiword token
+    /// that covers both the token before and after `index` is removed,
+    /// since it cannot cover the inserted token.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is 0 or larger than the number of
+    /// tokens plus one.
+    pub fn insert(&mut self, index: usize, token: Token) {
+        assert!(index > 0, "Cannot insert a token before the root");
+        assert!(index <= self.len(), "Index out of bounds");
+
+        let inserted = self.push(token);
+        let order = (1..index)
+            .chain(Some(inserted))
+            .chain(index..inserted)
+            .collect::<Vec<_>>();
+        self.reindex(&order);
+    }
+
+    /// Reorder the tokens of the sentence.
+    ///
+    /// `order[i]` is the current index of the token that is moved to
+    /// index `i + 1`. Relations, empty nodes and enhanced relations are
+    /// updated to use the new indices. Empty nodes move with the token
+    /// that they follow. Multiword tokens of which the tokens are no
+    /// longer adjacent and in order are removed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `order` is not a permutation of the token
+    /// indices `1..self.len()`.
+    pub fn permute(&mut self, order: &[usize]) {
+        let mut sorted = order.to_owned();
+        sorted.sort_unstable();
+        assert!(
+            sorted.into_iter().eq(1..self.len()),
+            "Order is not a permutation of the tokens"
+        );
+
+        self.reindex(order);
+    }
+
+    /// Remove the token at index `index`.
+    ///
+    /// The tokens after `index` are shifted one position to the left.
+    /// The dependents of the token are treated according to `policy` in
+    /// both the non-projective and projective layer. Relations, empty
+    /// nodes and enhanced relations are updated to use the new indices.
+    /// Empty nodes that follow the removed token now follow the token
+    /// before it. Enhanced relations of the removed token are removed, as
+    /// are multiword tokens that cover the token.
+    ///
+    /// Returns the removed token.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is 0 or out of bounds.
+    pub fn remove(&mut self, index: usize, policy: RemovalPolicy) -> Token {
+        assert!(index > 0, "Cannot remove the root");
+        assert!(index < self.len(), "Index out of bounds");
+
+        for &proj in &[Projectivity::NonProjective, Projectivity::Projective] {
+            let mut graph = DepGraphMut {
+                inner: &mut self.graph,
+                proj,
+            };
+            let head = graph.remove_head_rel(index).map(|triple| triple.head());
+            let dependents = graph
+                .dependents(index)
+                .map(|triple| triple.dependent())
+                .collect::<Vec<_>>();
+
+            for dependent in dependents {
+                let triple = graph.remove_head_rel(dependent).unwrap();
+                if let (RemovalPolicy::Reattach, Some(head)) = (policy, head) {
+                    graph.add_deprel(DepTriple::new(head, triple.relation, dependent));
+                }
+            }
+        }
+
+        let token = match &self[index] {
+            Node::Token(token) => token.clone(),
+            Node::Root | Node::Empty(_) => unreachable!(),
+        };
+
+        let order = (1..self.len())
+            .filter(|&token| token != index)
+            .collect::<Vec<_>>();
+        self.reindex(&order);
+
+        token
+    }
+
+    /// Append the tokens of another sentence.
+    ///
+    /// The tokens, relations, multiword tokens, empty nodes and enhanced
+    /// relations of `other` are added after those of this sentence. In
+    /// each layer, the root dependents of `other` are attached to the
+    /// first root dependent of this sentence with the relation
+    /// `relation`. If this sentence does not have a root dependent in a
+    /// layer, the root dependents of `other` remain attached to the root.
+    ///
+    /// The metadata of this sentence, such as `sent_id`, is kept and the
+    /// metadata of `other` is discarded. The exception is `text`: if
+    /// both sentences have a text, the texts are joined with a space,
+    /// otherwise the text is removed, since it would not cover all
+    /// tokens. Comments of `other` that are not metadata are added after
+    /// the comments of this sentence.
+    pub fn append(&mut self, other: Sentence, relation: Option<&str>) {
+        let offset = self.len() - 1;
+
+        let root_dependent = |proj| {
+            dependents_impl(&self.graph, proj, 0)
+                .map(|triple| triple.dependent())
+                .min()
+        };
+        let non_proj_head = root_dependent(Projectivity::NonProjective);
+        let proj_head = root_dependent(Projectivity::Projective);
+        let enhanced_head = self
+            .enhanced_dep_graph()
+            .dependents(0)
+            .map(|triple| triple.dependent())
+            .min();
+
+        let Sentence {
+            comments,
+            graph,
+            multiword_tokens,
+            empty_nodes,
+            enhanced_edges,
+        } = other;
+
+        let text = self.metadata().get("text").map(|&text| text.to_owned());
+        let mut other_text = None;
+        for comment in comments {
+            match parse_metadata(&comment) {
+                Some(("text", text)) => other_text = Some(text.to_owned()),
+                Some(_) => (),
+                None => self.comments.push(comment),
+            }
+        }
+
+        match (text, other_text) {
+            (Some(text), Some(other_text)) => {
+                self.set_metadata("text", &format!("{} {}", text, other_text))
+            }
+            (Some(_), None) => self.remove_metadata("text"),
+            (None, _) => (),
+        }
+
+        let (nodes, edges) = graph.into_nodes_edges();
+        for node in nodes.into_iter().skip(1) {
+            self.graph.add_node(node.weight);
+        }
+        for edge in edges {
+            let (head, dependent) = (edge.source().index(), edge.target().index());
+            let (proj, edge_relation) = edge.weight;
+            let root_head = match proj {
+                Projectivity::NonProjective => non_proj_head,
+                Projectivity::Projective => proj_head,
+            };
+            let (head, edge_relation) = match (head, root_head) {
+                (0, Some(head)) => (head, relation.map(ToOwned::to_owned)),
+                (0, None) => (0, edge_relation),
+                (head, _) => (head + offset, edge_relation),
+            };
+            self.graph.add_edge(
+                node_index(head),
+                node_index(dependent + offset),
+                (proj, edge_relation),
+            );
+        }
+
+        for mut multiword_token in multiword_tokens {
+            multiword_token.first += offset;
+            multiword_token.last += offset;
+            self.multiword_tokens.push(multiword_token);
+        }
+
+        // Empty nodes that follow the root of `other` follow the empty
+        // nodes after the last token of this sentence.
+        let preceding_empty = self
+            .empty_nodes
+            .range(NodeId::Token(offset)..NodeId::Token(offset + 1))
+            .count();
+        let map_id = |id: NodeId| match id {
+            NodeId::Token(0) => NodeId::Token(0),
+            NodeId::Token(token) => NodeId::Token(token + offset),
+            NodeId::Empty(0, position) => NodeId::Empty(offset, preceding_empty + position),
+            NodeId::Empty(token, position) => NodeId::Empty(token + offset, position),
+        };
+        for (id, node) in empty_nodes {
+            self.empty_nodes.insert(map_id(id), node);
+        }
+
+        for (dependent, head, edge_relation) in enhanced_edges {
+            let edge = match (head, enhanced_head) {
+                (NodeId::Token(0), Some(head)) => {
+                    (map_id(dependent), head, relation.map(ToOwned::to_owned))
+                }
+                _ => (map_id(dependent), map_id(head), edge_relation),
+            };
+            self.enhanced_edges.insert(edge);
+        }
+    }
+
+    /// Split the sentence at token index `index`.
+    ///
+    /// The tokens from `index` onwards are moved to the returned
+    /// sentence, together with their relations, multiword tokens, empty
+    /// nodes and enhanced relations. Relations between the root and a
+    /// token are preserved in both sentences. Other relations that cross
+    /// the split are treated according to `policy` in all layers.
+    /// Multiword tokens that cross the split are removed.
+    ///
+    /// The comments and metadata stay with this sentence, so the returned
+    /// sentence does not have a `sent_id`. The exception is `text`: it is
+    /// split between both sentences at the boundary of the surface forms.
+    /// If the text does not match the surface forms, or a multiword token
+    /// crosses the split, the text is removed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is 0 or larger than the number of
+    /// tokens plus one.
+    pub fn split_off(&mut self, index: usize, policy: SplitPolicy) -> Sentence {
+        assert!(index > 0, "Cannot split before the root");
+        assert!(index <= self.len(), "Index out of bounds");
+
+        let texts = self.split_text(index);
+        self.remove_metadata("text");
+        let mut second_comments = Vec::new();
+        if let Some((text, second_text)) = texts {
+            if !text.is_empty() {
+                self.set_metadata("text", &text);
+            }
+            if !second_text.is_empty() {
+                second_comments.push(format!("text = {}", second_text));
+            }
+        }
+
+        let crosses = |head: NodeId, dependent: NodeId| {
+            head != NodeId::Token(0) && (head.token() >= index) != (dependent.token() >= index)
+        };
+
+        for &proj in &[Projectivity::NonProjective, Projectivity::Projective] {
+            let mut graph = DepGraphMut {
+                inner: &mut self.graph,
+                proj,
+            };
+            let crossing = (1..graph.len())
+                .filter_map(|dependent| graph.head(dependent))
+                .filter(|triple| {
+                    crosses(
+                        NodeId::Token(triple.head()),
+                        NodeId::Token(triple.dependent()),
+                    )
+                })
+                .map(|triple| triple.dependent())
+                .collect::<Vec<_>>();
+
+            for dependent in crossing {
+                let triple = graph.remove_head_rel(dependent).unwrap();
+                if policy == SplitPolicy::AttachToRoot {
+                    graph.add_deprel(DepTriple::new(0, triple.relation, dependent));
+                }
+            }
+        }
+
+        self.enhanced_edges = mem::take(&mut self.enhanced_edges)
+            .into_iter()
+            .filter_map(|(dependent, head, relation)| {
+                if !crosses(head, dependent) {
+                    Some((dependent, head, relation))
+                } else if policy == SplitPolicy::AttachToRoot {
+                    Some((dependent, NodeId::Token(0), relation))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut second = Sentence {
+            comments: second_comments,
+            graph: self.graph.clone(),
+            multiword_tokens: self.multiword_tokens.clone(),
+            empty_nodes: self.empty_nodes.split_off(&NodeId::Token(index)),
+            enhanced_edges: self.enhanced_edges.clone(),
+        };
+
+        self.reindex(&(1..index).collect::<Vec<_>>());
+        let len = second.len();
+        second.reindex(&(index..len).collect::<Vec<_>>());
+
+        second
+    }
+
+    /// Split the `text` metadata before the surface token that starts at
+    /// token index `index`.
+    ///
+    /// Returns `None` if the sentence does not have a text, the text does
+    /// not match the surface forms, or a multiword token crosses the
+    /// split.
+    fn split_text(&self, index: usize) -> Option<(String, String)> {
+        let text = *self.metadata().get("text")?;
+
+        let mut offset = 0;
+        let mut token = 1;
+        while token < index {
+            let (form, last) = match self.multiword_token(token) {
+                Some(multiword_token) => (multiword_token.form(), multiword_token.last()),
+                None => (self[token].token()?.form(), token),
+            };
+            if last >= index {
+                return None;
+            }
+
+            let start = text.len() - text[offset..].trim_start().len();
+            if !text[start..].starts_with(form) {
+                return None;
+            }
+
+            offset = start + form.len();
+            token = last + 1;
+        }
+
+        Some((
+            text[..offset].trim_end().to_owned(),
+            text[offset..].trim_start().to_owned(),
+        ))
+    }
+
+    /// Remove all `key = value` comments with the given key.
+    fn remove_metadata(&mut self, key: &str) {
+        self.comments
+            .retain(|comment| parse_metadata(comment).map(|(k, _)| k) != Some(key));
     }
 
     /// Get the non-projective dependency graph.
-    pub fn dep_graph(&self) -> DepGraph {
+    pub fn dep_graph(&self) -> DepGraph<'_> {
         DepGraph {
-            inner: &self.0,
+            inner: &self.graph,
             proj: Projectivity::NonProjective,
         }
     }
 
     /// Get the non-projective graph mutably.
-    pub fn dep_graph_mut(&mut self) -> DepGraphMut {
+    pub fn dep_graph_mut(&mut self) -> DepGraphMut<'_> {
         DepGraphMut {
-            inner: &mut self.0,
+            inner: &mut self.graph,
             proj: Projectivity::NonProjective,
         }
     }
 
     /// Get the projective graph.
-    pub fn proj_dep_graph(&self) -> DepGraph {
+    pub fn proj_dep_graph(&self) -> DepGraph<'_> {
         DepGraph {
-            inner: &self.0,
+            inner: &self.graph,
             proj: Projectivity::Projective,
         }
     }
 
     /// Get the projective graph mutably.
-    pub fn proj_dep_graph_mut(&mut self) -> DepGraphMut {
+    pub fn proj_dep_graph_mut(&mut self) -> DepGraphMut<'_> {
         DepGraphMut {
-            inner: &mut self.0,
+            inner: &mut self.graph,
             proj: Projectivity::Projective,
         }
     }
 
+    /// Get the enhanced dependency graph.
+    pub fn enhanced_dep_graph(&self) -> EnhancedDepGraph<'_> {
+        EnhancedDepGraph { inner: self }
+    }
+
+    /// Get the enhanced dependency graph mutably.
+    pub fn enhanced_dep_graph_mut(&mut self) -> EnhancedDepGraphMut<'_> {
+        EnhancedDepGraphMut { inner: self }
+    }
+
     /// Get the number of nodes in the dependency graph.
     ///
     /// This is equal to the number of tokens, plus one root node.
     pub fn len(&self) -> usize {
-        self.0.node_count()
+        self.graph.node_count()
     }
-}
 
-impl Default for Sentence {
-    fn default() -> Self {
-        Sentence::new()
-    }
-}
+    /// Add a multiword token.
+    ///
+    /// Multiword tokens form a layer over the tokens of the dependency
+    /// graph: a multiword token is the surface form of a range of
+    /// tokens. For example, German *zum* is a multiword token that
+    /// consists of the tokens *zu* and *dem*.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range of the multiword token is not
+    /// within the sentence or if it overlaps with the range of an existing
+    /// multiword token.
+    pub fn add_multiword_token(&mut self, multiword_token: MultiwordToken) {
+        assert!(multiword_token.first() > 0, "Range starts at the root");
+        assert!(multiword_token.last() < self.len(), "Range out of bounds");
+
+        let idx = match self
+            .multiword_tokens
+            .binary_search_by_key(&multiword_token.first(), MultiwordToken::first)
+        {
+            Ok(_) => panic!("Range overlaps with an existing multiword token"),
+            Err(idx) => idx,
+        };
+
+        if let Some(prev) = idx.checked_sub(1).map(|idx| &self.multiword_tokens[idx]) {
+            assert!(
+                prev.last() < multiword_token.first(),
+                "Range overlaps with an existing multiword token"
+            );
+        }
 
-impl Display for Sentence {
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
-        for i in 1..self.len() {
-            let token = match self[i] {
-                Node::Token(ref token) => token,
-                Node::Root => unreachable!(),
-            };
+        if let Some(next) = self.multiword_tokens.get(idx) {
+            assert!(
+                multiword_token.last() < next.first(),
+                "Range overlaps with an existing multiword token"
+            );
+        }
 
-            let (head, head_rel) = triple_to_string(&self.dep_graph(), i);
-            let (phead, phead_rel) = triple_to_string(&self.proj_dep_graph(), i);
+        self.multiword_tokens.insert(idx, multiword_token);
+    }
 
-            writeln!(
-                fmt,
-                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
-                i,
-                token.form(),
-                token.lemma().unwrap_or("_"),
-                token.cpos().unwrap_or("_"),
-                token.pos().unwrap_or("_"),
-                token.features().map(Into::into).unwrap_or("_".to_string()),
-                head.unwrap_or_else(|| "_".to_string()),
-                head_rel.unwrap_or_else(|| "_".to_string()),
-                phead.unwrap_or_else(|| "_".to_string()),
-                phead_rel.unwrap_or_else(|| "_".to_string()),
-            )?;
-        }
+    /// Add an empty node.
+    ///
+    /// The empty node is placed after the token at index `token` and
+    /// after existing empty nodes that follow that token. Use index 0 to
+    /// add an empty node before the first token.
+    ///
+    /// Returns the identifier of the empty node.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the token index is out of bounds.
+    pub fn add_empty_node(&mut self, token: usize, empty_node: Token) -> NodeId {
+        assert!(token < self.len(), "Token out of bounds");
+
+        let position = self
+            .empty_nodes
+            .range(NodeId::Token(token)..NodeId::Token(token + 1))
+            .next_back()
+            .map(|(id, _)| id.sort_key().1)
+            .unwrap_or(0)
+            + 1;
+
+        let id = NodeId::Empty(token, position);
+        self.empty_nodes.insert(id, Node::Empty(empty_node));
+
+        id
+    }
 
-        Ok(())
+    /// Get an iterator over the empty nodes, ordered by position.
+    pub fn empty_nodes(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.empty_nodes.iter().map(|(&id, node)| (id, node))
     }
-}
 
-impl FromIterator<Token> for Sentence {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = Token>,
-    {
-        let mut sentence = Sentence::new();
-        for token in iter {
-            sentence.push(token);
+    /// Get the node with identifier `id`.
+    ///
+    /// Returns `None` if there is no such node.
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        match id {
+            NodeId::Token(token) if token < self.len() => Some(&self[token]),
+            NodeId::Token(_) => None,
+            NodeId::Empty(_, _) => self.empty_nodes.get(&id),
         }
-        sentence
     }
-}
 
-/// Iterator over the nodes in a dependency graph.
+    /// Get the node with identifier `id` mutably.
+    ///
+    /// Returns `None` if there is no such node.
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        match id {
+            NodeId::Token(token) if token < self.len() => Some(&mut self[token]),
+            NodeId::Token(_) => None,
+            NodeId::Empty(_, _) => self.empty_nodes.get_mut(&id),
+        }
+    }
+
+    /// Get the multiword token that covers the token at index `token`.
+    ///
+    /// Returns `None` if the token is not part of a multiword token.
+    pub fn multiword_token(&self, token: usize) -> Option<&MultiwordToken> {
+        self.multiword_token_idx(token)
+            .map(|idx| &self.multiword_tokens[idx])
+    }
+
+    /// Get the multiword tokens of the sentence, ordered by position.
+    pub fn multiword_tokens(&self) -> &[MultiwordToken] {
+        &self.multiword_tokens
+    }
+
+    /// Remove the multiword token that covers the token at index `token`.
+    ///
+    /// Returns the removed multiword token, if any.
+    pub fn remove_multiword_token(&mut self, token: usize) -> Option<MultiwordToken> {
+        self.multiword_token_idx(token)
+            .map(|idx| self.multiword_tokens.remove(idx))
+    }
+
+    /// Reindex the tokens of the sentence.
+    ///
+    /// `order[i]` is the current index of the token that is moved to
+    /// index `i + 1`. Tokens that are not in `order` are removed, along
+    /// with their relations.
+    fn reindex(&mut self, order: &[usize]) {
+        let mut mapping = vec![None; self.len()];
+        mapping[0] = Some(0);
+        for (new, &old) in order.iter().enumerate() {
+            mapping[old] = Some(new + 1);
+        }
+
+        // Rebuild the dependency graph with the new node order.
+        let (nodes, edges) = mem::replace(&mut self.graph, DiGraph::new()).into_nodes_edges();
+        let mut nodes = nodes
+            .into_iter()
+            .map(|node| Some(node.weight))
+            .collect::<Vec<_>>();
+        self.graph.add_node(nodes[0].take().unwrap());
+        for &old in order {
+            self.graph
+                .add_node(nodes[old].take().expect("Token occurs more than once"));
+        }
+        for edge in edges {
+            if let (Some(head), Some(dependent)) = (
+                mapping[edge.source().index()],
+                mapping[edge.target().index()],
+            ) {
+                self.graph
+                    .add_edge(node_index(head), node_index(dependent), edge.weight);
+            }
+        }
+
+        // Keep multiword tokens of which the tokens are still adjacent.
+        let mut multiword_tokens = mem::take(&mut self.multiword_tokens)
+            .into_iter()
+            .filter_map(|mut multiword_token| {
+                let first = mapping[multiword_token.first]?;
+                for (offset, token) in multiword_token.range().enumerate() {
+                    if mapping[token] != Some(first + offset) {
+                        return None;
+                    }
+                }
+
+                multiword_token.last = first + multiword_token.last - multiword_token.first;
+                multiword_token.first = first;
+                Some(multiword_token)
+            })
+            .collect::<Vec<_>>();
+        multiword_tokens.sort_by_key(MultiwordToken::first);
+        self.multiword_tokens = multiword_tokens;
+
+        // Empty nodes follow the nearest preceding token that is kept.
+        let mut preceding = mapping.clone();
+        for token in 1..preceding.len() {
+            if preceding[token].is_none() {
+                preceding[token] = preceding[token - 1];
+            }
+        }
+
+        let mut empty_nodes = mem::take(&mut self.empty_nodes)
+            .into_iter()
+            .map(|(id, node)| (preceding[id.token()].unwrap(), id, node))
+            .collect::<Vec<_>>();
+        empty_nodes.sort_by_key(|&(token, _, _)| token);
+
+        let mut id_mapping = BTreeMap::new();
+        let mut prev_token = None;
+        let mut position = 0;
+        for (token, id, node) in empty_nodes {
+            position = if prev_token == Some(token) {
+                position + 1
+            } else {
+                1
+            };
+            prev_token = Some(token);
+
+            let new_id = NodeId::Empty(token, position);
+            id_mapping.insert(id, new_id);
+            self.empty_nodes.insert(new_id, node);
+        }
+
+        // Update enhanced relations, removing relations of removed tokens.
+        let map_id = |id: NodeId| match id {
+            NodeId::Token(token) => mapping[token].map(NodeId::Token),
+            NodeId::Empty(_, _) => id_mapping.get(&id).cloned(),
+        };
+        self.enhanced_edges = mem::take(&mut self.enhanced_edges)
+            .into_iter()
+            .filter_map(|(dependent, head, relation)| {
+                Some((map_id(dependent)?, map_id(head)?, relation))
+            })
+            .collect();
+    }
+
+    fn multiword_token_idx(&self, token: usize) -> Option<usize> {
+        let idx = match self
+            .multiword_tokens
+            .binary_search_by_key(&token, MultiwordToken::first)
+        {
+            Ok(idx) => return Some(idx),
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+
+        if self.multiword_tokens[idx].contains(token) {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Sentence {
+    fn default() -> Self {
+        Sentence::new()
+    }
+}
+
+impl Display for Sentence {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        for comment in &self.comments {
+            writeln!(fmt, "# {}", comment)?;
+        }
+
+        for i in 1..self.len() {
+            let token = match self[i] {
+                Node::Token(ref token) => token,
+                Node::Root | Node::Empty(_) => unreachable!(),
+            };
+
+            let (head, head_rel) = triple_to_string(&self.dep_graph(), i);
+            let (phead, phead_rel) = triple_to_string(&self.proj_dep_graph(), i);
+
+            writeln!(
+                fmt,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                i,
+                token.form(),
+                token.lemma().unwrap_or("_"),
+                token.cpos().unwrap_or("_"),
+                token.pos().unwrap_or("_"),
+                token.features().map(Into::into).unwrap_or("_".to_string()),
+                head.unwrap_or_else(|| "_".to_string()),
+                head_rel.unwrap_or_else(|| "_".to_string()),
+                phead.unwrap_or_else(|| "_".to_string()),
+                phead_rel.unwrap_or_else(|| "_".to_string()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromIterator<Token> for Sentence {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = Token>,
+    {
+        let mut sentence = Sentence::new();
+        for token in iter {
+            sentence.push(token);
+        }
+        sentence
+    }
+}
+
+/// Iterator over the nodes in a dependency graph.
 pub struct Iter<'a> {
     inner: NodeIndices,
     graph: &'a DiGraph<Node, Edge>,
@@ -306,6 +1197,13 @@ impl<'a> IntoIterator for &'a mut Sentence {
     }
 }
 
+/// Split a `key = value` comment into its key and value.
+fn parse_metadata(comment: &str) -> Option<(&str, &str)> {
+    comment
+        .find('=')
+        .map(|idx| (comment[..idx].trim(), comment[idx + 1..].trim()))
+}
+
 fn triple_to_string(g: &DepGraph, dependent: usize) -> (Option<String>, Option<String>) {
     //  XXX:return string reference for relation.
     let head_triple = g.head(dependent);
@@ -335,19 +1233,150 @@ impl Index<usize> for Sentence {
     type Output = Node;
 
     fn index(&self, idx: usize) -> &Self::Output {
-        &self.0[node_index(idx)]
+        &self.graph[node_index(idx)]
     }
 }
 
 impl IndexMut<usize> for Sentence {
     fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
-        &mut self.0[node_index(idx)]
+        &mut self.graph[node_index(idx)]
     }
 }
 
 impl PartialEq for Sentence {
     fn eq(&self, other: &Self) -> bool {
-        self.dep_graph() == other.dep_graph() && self.proj_dep_graph() == other.proj_dep_graph()
+        self.comments == other.comments
+            && self.dep_graph() == other.dep_graph()
+            && self.proj_dep_graph() == other.proj_dep_graph()
+            && self.multiword_tokens == other.multiword_tokens
+            && self.empty_nodes == other.empty_nodes
+            && self.enhanced_edges == other.enhanced_edges
+    }
+}
+
+/// Direction in which a relation is traversed on a dependency path.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PathDirection {
+    /// From the dependent to the head.
+    Up,
+
+    /// From the head to the dependent.
+    Down,
+}
+
+/// Precomputed ancestors of the tokens in a dependency graph.
+///
+/// This data structure answers lowest common ancestor and path queries
+/// in *O(log n)* time (excluding the length of the path), after
+/// *O(n log n)* preprocessing. This is faster than the corresponding
+/// methods of `DepGraph` when many queries are made on the same graph.
+///
+/// ```
+/// use conllx::graph::{Ancestors, DepTriple, PathDirection, Sentence};
+/// use conllx::token::Token;
+///
+/// let mut sentence = Sentence::new();
+/// sentence.push(Token::new("Hello"));
+/// sentence.push(Token::new("beautiful"));
+/// sentence.push(Token::new("world"));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(0, Some("ROOT"), 1));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(1, Some("OBJ"), 3));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(3, Some("ATTR"), 2));
+///
+/// let graph = sentence.dep_graph();
+/// let ancestors = Ancestors::new(&graph);
+/// assert_eq!(ancestors.lowest_common_ancestor(1, 2), Some(1));
+/// assert_eq!(
+///     ancestors.path(2, 1),
+///     Some(vec![
+///         (PathDirection::Up, DepTriple::new(3, Some("ATTR"), 2)),
+///         (PathDirection::Up, DepTriple::new(1, Some("OBJ"), 3)),
+///     ])
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ancestors<'a> {
+    heads: Vec<Option<DepTriple<&'a str>>>,
+    depths: Vec<Option<usize>>,
+
+    /// The ancestors at distance `2^i` of each token, indexed by `i`.
+    /// The root is its own ancestor.
+    jumps: Vec<Vec<usize>>,
+}
+
+impl<'a> Ancestors<'a> {
+    /// Precompute the ancestors of the tokens in `graph`.
+    pub fn new(graph: &DepGraph<'a>) -> Self {
+        let heads = (0..graph.len())
+            .map(|token| graph.head(token))
+            .collect::<Vec<_>>();
+        let depths = (0..graph.len())
+            .map(|token| depth_impl(graph.inner, graph.proj, token))
+            .collect::<Vec<_>>();
+
+        let mut jumps = vec![heads
+            .iter()
+            .zip(&depths)
+            .map(|(head, depth)| match (head, depth) {
+                (Some(head), Some(_)) => head.head(),
+                _ => 0,
+            })
+            .collect::<Vec<_>>()];
+        let max_depth = depths.iter().filter_map(|&depth| depth).max().unwrap_or(0);
+        while 1 << jumps.len() <= max_depth {
+            let prev = jumps.last().unwrap();
+            let level = prev.iter().map(|&ancestor| prev[ancestor]).collect();
+            jumps.push(level);
+        }
+
+        Ancestors {
+            heads,
+            depths,
+            jumps,
+        }
+    }
+
+    /// Return the lowest common ancestor of `token1` and `token2`.
+    ///
+    /// See `DepGraph::lowest_common_ancestor`.
+    pub fn lowest_common_ancestor(&self, token1: usize, token2: usize) -> Option<usize> {
+        let (mut token1, mut token2) = (token1, token2);
+        let (mut depth1, mut depth2) = (self.depths[token1]?, self.depths[token2]?);
+        if depth1 < depth2 {
+            mem::swap(&mut token1, &mut token2);
+            mem::swap(&mut depth1, &mut depth2);
+        }
+
+        // Lift the deepest token to the depth of the other token.
+        for (level, ancestors) in self.jumps.iter().enumerate() {
+            if (depth1 - depth2) & (1 << level) != 0 {
+                token1 = ancestors[token1];
+            }
+        }
+
+        if token1 == token2 {
+            return Some(token1);
+        }
+
+        // Lift both tokens to just below their lowest common ancestor.
+        for ancestors in self.jumps.iter().rev() {
+            if ancestors[token1] != ancestors[token2] {
+                token1 = ancestors[token1];
+                token2 = ancestors[token2];
+            }
+        }
+
+        Some(self.jumps[0][token1])
+    }
+
+    /// Return the dependency path from `from` to `to`.
+    ///
+    /// See `DepGraph::path`.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<(PathDirection, DepTriple<&'a str>)>> {
+        let ancestor = self.lowest_common_ancestor(from, to)?;
+        Some(path_via(from, to, ancestor, |token| {
+            self.heads[token].clone().unwrap()
+        }))
     }
 }
 
@@ -368,17 +1397,73 @@ impl<'a> DepGraph<'a> {
         dependents_impl(self.inner, self.proj, head)
     }
 
+    /// Return the transitive dependents of `head`, ordered by index.
+    pub fn descendants(&self, head: usize) -> Vec<usize> {
+        descendants_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the depth of `token`.
+    ///
+    /// The depth is the number of relations on the path from the root to
+    /// `token`. Returns `None` if `token` is not connected to the root.
+    pub fn depth(&self, token: usize) -> Option<usize> {
+        depth_impl(self.inner, self.proj, token)
+    }
+
     /// Return the head relation of `dependent`, if any.
     pub fn head(&self, dependent: usize) -> Option<DepTriple<&'a str>> {
         head_impl(self.inner, self.proj, dependent)
     }
 
+    /// Check whether the yield of `head` is contiguous.
+    ///
+    /// The yield is contiguous when it contains all the tokens in the
+    /// span of `head`.
+    pub fn is_contiguous(&self, head: usize) -> bool {
+        contiguous_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the lowest common ancestor of `token1` and `token2`.
+    ///
+    /// The lowest common ancestor is the deepest token that dominates
+    /// both tokens, where a token dominates itself. Returns `None` if
+    /// one of the tokens is not connected to the root.
+    pub fn lowest_common_ancestor(&self, token1: usize, token2: usize) -> Option<usize> {
+        lowest_common_ancestor_impl(self.inner, self.proj, token1, token2)
+    }
+
+    /// Return the span of `head`.
+    ///
+    /// The span is the pair of the leftmost and rightmost tokens of the
+    /// yield of `head`.
+    pub fn span(&self, head: usize) -> (usize, usize) {
+        span_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the yield of `head`.
+    ///
+    /// The yield of a token consists of the token itself and its
+    /// transitive dependents, ordered by index.
+    pub fn token_yield(&self, head: usize) -> Vec<usize> {
+        yield_impl(self.inner, self.proj, head)
+    }
+
     /// Get the number of nodes in the dependency graph.
     ///
     /// This is equal to the number of tokens, plus one root node.
     pub fn len(&self) -> usize {
         self.inner.node_count()
     }
+
+    /// Return the dependency path from `from` to `to`.
+    ///
+    /// The path goes up from `from` to the lowest common ancestor of the
+    /// tokens and then down to `to`. Each relation on the path is marked
+    /// with the direction in which it is traversed. Returns `None` if one
+    /// of the tokens is not connected to the root.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<(PathDirection, DepTriple<&'a str>)>> {
+        path_impl(self.inner, self.proj, from, to)
+    }
 }
 
 impl<'a> Eq for DepGraph<'a> {}
@@ -469,11 +1554,41 @@ impl<'a> DepGraphMut<'a> {
         dependents_impl(self.inner, self.proj, head)
     }
 
+    /// Return the transitive dependents of `head`, ordered by index.
+    pub fn descendants(&self, head: usize) -> Vec<usize> {
+        descendants_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the depth of `token`.
+    ///
+    /// The depth is the number of relations on the path from the root to
+    /// `token`. Returns `None` if `token` is not connected to the root.
+    pub fn depth(&self, token: usize) -> Option<usize> {
+        depth_impl(self.inner, self.proj, token)
+    }
+
     /// Return the head relation of `dependent`, if any.
     pub fn head(&self, dependent: usize) -> Option<DepTriple<&str>> {
         head_impl(self.inner, self.proj, dependent)
     }
 
+    /// Check whether the yield of `head` is contiguous.
+    ///
+    /// The yield is contiguous when it contains all the tokens in the
+    /// span of `head`.
+    pub fn is_contiguous(&self, head: usize) -> bool {
+        contiguous_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the lowest common ancestor of `token1` and `token2`.
+    ///
+    /// The lowest common ancestor is the deepest token that dominates
+    /// both tokens, where a token dominates itself. Returns `None` if
+    /// one of the tokens is not connected to the root.
+    pub fn lowest_common_ancestor(&self, token1: usize, token2: usize) -> Option<usize> {
+        lowest_common_ancestor_impl(self.inner, self.proj, token1, token2)
+    }
+
     /// Remove relation of a token to its head.
     ///
     /// Returns the index of the head iff a head was removed.
@@ -501,6 +1616,32 @@ impl<'a> DepGraphMut<'a> {
     pub fn len(&self) -> usize {
         self.inner.node_count()
     }
+
+    /// Return the dependency path from `from` to `to`.
+    ///
+    /// The path goes up from `from` to the lowest common ancestor of the
+    /// tokens and then down to `to`. Each relation on the path is marked
+    /// with the direction in which it is traversed. Returns `None` if one
+    /// of the tokens is not connected to the root.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<(PathDirection, DepTriple<&str>)>> {
+        path_impl(self.inner, self.proj, from, to)
+    }
+
+    /// Return the span of `head`.
+    ///
+    /// The span is the pair of the leftmost and rightmost tokens of the
+    /// yield of `head`.
+    pub fn span(&self, head: usize) -> (usize, usize) {
+        span_impl(self.inner, self.proj, head)
+    }
+
+    /// Return the yield of `head`.
+    ///
+    /// The yield of a token consists of the token itself and its
+    /// transitive dependents, ordered by index.
+    pub fn token_yield(&self, head: usize) -> Vec<usize> {
+        yield_impl(self.inner, self.proj, head)
+    }
 }
 
 impl<'a> Index<usize> for DepGraphMut<'a> {
@@ -517,6 +1658,175 @@ impl<'a> IndexMut<usize> for DepGraphMut<'a> {
     }
 }
 
+/// An enhanced dependency graph view.
+///
+/// This data structure provides a view of the enhanced dependency graph
+/// of a sentence. In contrast to `DepGraph`, nodes in the enhanced graph
+/// can have multiple heads and the graph can contain cycles. Besides
+/// tokens, empty nodes can be heads and dependents.
+pub struct EnhancedDepGraph<'a> {
+    inner: &'a Sentence,
+}
+
+impl<'a> EnhancedDepGraph<'a> {
+    /// Return an iterator over the dependents of `head`.
+    pub fn dependents(
+        &self,
+        head: impl Into<NodeId>,
+    ) -> impl Iterator<Item = EnhancedDepTriple<&'a str>> {
+        enhanced_dependents_impl(&self.inner.enhanced_edges, head.into())
+    }
+
+    /// Return an iterator over the head relations of `dependent`.
+    ///
+    /// The relations are ordered by head.
+    pub fn heads(
+        &self,
+        dependent: impl Into<NodeId>,
+    ) -> impl Iterator<Item = EnhancedDepTriple<&'a str>> {
+        enhanced_heads_impl(&self.inner.enhanced_edges, dependent.into())
+    }
+
+    /// Return an iterator over all relations in the graph.
+    ///
+    /// The relations are ordered by dependent and then by head.
+    pub fn triples(&self) -> impl Iterator<Item = EnhancedDepTriple<&'a str>> {
+        self.inner.enhanced_edges.iter().map(edge_to_triple)
+    }
+
+    /// Get the node with identifier `id`.
+    pub fn node(&self, id: NodeId) -> Option<&'a Node> {
+        self.inner.node(id)
+    }
+}
+
+/// A mutable enhanced dependency graph view.
+///
+/// This data structure provides a mutable view of the enhanced dependency
+/// graph of a sentence. Different from `DepGraphMut`, adding a relation
+/// does not remove existing head relations of the dependent.
+pub struct EnhancedDepGraphMut<'a> {
+    inner: &'a mut Sentence,
+}
+
+impl<'a> EnhancedDepGraphMut<'a> {
+    /// Add an enhanced dependency relation.
+    ///
+    /// Returns `false` if the relation was already present.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the head or dependent is not a node of
+    /// the sentence.
+    pub fn add_deprel<S>(&mut self, triple: EnhancedDepTriple<S>) -> bool
+    where
+        S: Into<String>,
+    {
+        assert!(
+            self.inner.node(triple.head()).is_some(),
+            "Head out of bounds"
+        );
+        assert!(
+            self.inner.node(triple.dependent()).is_some(),
+            "dependent out of bounds"
+        );
+
+        self.inner.enhanced_edges.insert((
+            triple.dependent,
+            triple.head,
+            triple.relation.map(Into::into),
+        ))
+    }
+
+    /// Return an iterator over the dependents of `head`.
+    pub fn dependents(
+        &self,
+        head: impl Into<NodeId>,
+    ) -> impl Iterator<Item = EnhancedDepTriple<&str>> {
+        enhanced_dependents_impl(&self.inner.enhanced_edges, head.into())
+    }
+
+    /// Return an iterator over the head relations of `dependent`.
+    ///
+    /// The relations are ordered by head.
+    pub fn heads(
+        &self,
+        dependent: impl Into<NodeId>,
+    ) -> impl Iterator<Item = EnhancedDepTriple<&str>> {
+        enhanced_heads_impl(&self.inner.enhanced_edges, dependent.into())
+    }
+
+    /// Remove an enhanced dependency relation.
+    ///
+    /// Returns `false` if the relation was not present.
+    pub fn remove_deprel<S>(&mut self, triple: &EnhancedDepTriple<S>) -> bool
+    where
+        S: Borrow<str>,
+    {
+        self.inner.enhanced_edges.remove(&(
+            triple.dependent,
+            triple.head,
+            triple.relation().map(ToOwned::to_owned),
+        ))
+    }
+
+    /// Remove all relations of a node to its heads.
+    ///
+    /// Returns the removed relations.
+    pub fn remove_head_rels(
+        &mut self,
+        dependent: impl Into<NodeId>,
+    ) -> Vec<EnhancedDepTriple<String>> {
+        let dependent = dependent.into();
+        let edges = self
+            .inner
+            .enhanced_edges
+            .range((dependent, NodeId::Token(0), None)..)
+            .take_while(|(edge_dependent, _, _)| *edge_dependent == dependent)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        edges
+            .into_iter()
+            .map(|edge| {
+                self.inner.enhanced_edges.remove(&edge);
+                let (dependent, head, relation) = edge;
+                EnhancedDepTriple::new(head, relation, dependent)
+            })
+            .collect()
+    }
+}
+
+fn edge_to_triple(edge: &EnhancedEdge) -> EnhancedDepTriple<&str> {
+    let (dependent, head, relation) = edge;
+    EnhancedDepTriple::new(*head, relation.as_deref(), *dependent)
+}
+
+fn enhanced_dependents_impl(
+    edges: &BTreeSet<EnhancedEdge>,
+    head: NodeId,
+) -> impl Iterator<Item = EnhancedDepTriple<&str>> {
+    edges
+        .iter()
+        .filter(move |(_, edge_head, _)| *edge_head == head)
+        .map(edge_to_triple)
+}
+
+fn enhanced_heads_impl(
+    edges: &BTreeSet<EnhancedEdge>,
+    dependent: NodeId,
+) -> impl Iterator<Item = EnhancedDepTriple<&str>> {
+    edges
+        .range((dependent, NodeId::Token(0), None)..)
+        .take_while(move |(edge_dependent, _, _)| *edge_dependent == dependent)
+        .map(edge_to_triple)
+}
+
+fn contiguous_impl(graph: &DiGraph<Node, Edge>, proj: Projectivity, head: usize) -> bool {
+    let token_yield = yield_impl(graph, proj, head);
+    token_yield[token_yield.len() - 1] - token_yield[0] + 1 == token_yield.len()
+}
+
 fn dependents_impl(
     graph: &DiGraph<Node, Edge>,
     proj: Projectivity,
@@ -528,12 +1838,49 @@ fn dependents_impl(
         .map(|e| {
             DepTriple::new(
                 e.source().index(),
-                e.weight().1.as_ref().map(String::as_str),
+                e.weight().1.as_deref(),
                 e.target().index(),
             )
         })
 }
 
+fn depth_impl(graph: &DiGraph<Node, Edge>, proj: Projectivity, token: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut current = token;
+    while current != 0 {
+        // A path that is longer than the number of tokens contains a cycle.
+        if depth == graph.node_count() {
+            return None;
+        }
+
+        current = head_impl(graph, proj, current)?.head();
+        depth += 1;
+    }
+
+    Some(depth)
+}
+
+fn descendants_impl(graph: &DiGraph<Node, Edge>, proj: Projectivity, head: usize) -> Vec<usize> {
+    let mut descendants = Vec::new();
+    let mut seen = vec![false; graph.node_count()];
+    seen[head] = true;
+
+    let mut stack = vec![head];
+    while let Some(token) = stack.pop() {
+        for triple in dependents_impl(graph, proj, token) {
+            // Tokens are seen twice when the relations contain a cycle.
+            if !seen[triple.dependent()] {
+                seen[triple.dependent()] = true;
+                descendants.push(triple.dependent());
+                stack.push(triple.dependent());
+            }
+        }
+    }
+
+    descendants.sort_unstable();
+    descendants
+}
+
 fn head_impl(
     graph: &DiGraph<Node, Edge>,
     proj: Projectivity,
@@ -545,15 +1892,97 @@ fn head_impl(
         .map(|e| {
             DepTriple::new(
                 e.source().index(),
-                e.weight().1.as_ref().map(String::as_str),
+                e.weight().1.as_deref(),
                 e.target().index(),
             )
         })
 }
 
+fn lowest_common_ancestor_impl(
+    graph: &DiGraph<Node, Edge>,
+    proj: Projectivity,
+    token1: usize,
+    token2: usize,
+) -> Option<usize> {
+    let (mut token1, mut token2) = (token1, token2);
+    let mut depth1 = depth_impl(graph, proj, token1)?;
+    let mut depth2 = depth_impl(graph, proj, token2)?;
+
+    while token1 != token2 {
+        if depth1 >= depth2 {
+            token1 = head_impl(graph, proj, token1)?.head();
+            depth1 -= 1;
+        } else {
+            token2 = head_impl(graph, proj, token2)?.head();
+            depth2 -= 1;
+        }
+    }
+
+    Some(token1)
+}
+
+fn path_impl(
+    graph: &DiGraph<Node, Edge>,
+    proj: Projectivity,
+    from: usize,
+    to: usize,
+) -> Option<Vec<(PathDirection, DepTriple<&str>)>> {
+    let ancestor = lowest_common_ancestor_impl(graph, proj, from, to)?;
+    Some(path_via(from, to, ancestor, |token| {
+        head_impl(graph, proj, token).unwrap()
+    }))
+}
+
+/// Construct the path from `from` to `to` through their lowest common
+/// ancestor `ancestor`.
+fn path_via<'a>(
+    from: usize,
+    to: usize,
+    ancestor: usize,
+    head: impl Fn(usize) -> DepTriple<&'a str>,
+) -> Vec<(PathDirection, DepTriple<&'a str>)> {
+    let mut path = Vec::new();
+
+    let mut token = from;
+    while token != ancestor {
+        let triple = head(token);
+        token = triple.head();
+        path.push((PathDirection::Up, triple));
+    }
+
+    let up_len = path.len();
+    let mut token = to;
+    while token != ancestor {
+        let triple = head(token);
+        token = triple.head();
+        path.push((PathDirection::Down, triple));
+    }
+    path[up_len..].reverse();
+
+    path
+}
+
+fn span_impl(graph: &DiGraph<Node, Edge>, proj: Projectivity, head: usize) -> (usize, usize) {
+    let token_yield = yield_impl(graph, proj, head);
+    (token_yield[0], token_yield[token_yield.len() - 1])
+}
+
+fn yield_impl(graph: &DiGraph<Node, Edge>, proj: Projectivity, head: usize) -> Vec<usize> {
+    let mut token_yield = descendants_impl(graph, proj, head);
+    let idx = token_yield.binary_search(&head).unwrap_err();
+    token_yield.insert(idx, head);
+    token_yield
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{DepTriple, Node, Sentence, Token};
+    use maplit::btreemap;
+
+    use super::{
+        Ancestors, DepTriple, EnhancedDepTriple, MultiwordToken, Node, NodeId, PathDirection,
+        RemovalPolicy, Sentence, SplitPolicy, Token,
+    };
+    use crate::tests::read_sentences;
 
     #[test]
     fn add_deprel() {
@@ -591,6 +2020,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn metadata() {
+        let mut g = Sentence::new();
+        g.add_comment("sent_id = 1");
+        g.add_comment("a comment");
+        g.add_comment("text=Hallo Welt");
+        g.push(Token::new("Hallo"));
+        g.push(Token::new("Welt"));
+
+        assert_eq!(
+            g.metadata(),
+            btreemap! {
+                "sent_id" => "1",
+                "text" => "Hallo Welt",
+            }
+        );
+
+        let g2 = g.clone();
+        g.set_metadata("sent_id", "2");
+        g.set_metadata("newdoc id", "doc1");
+        assert_ne!(g, g2);
+
+        assert_eq!(
+            g.comments(),
+            &[
+                "sent_id = 2",
+                "a comment",
+                "text=Hallo Welt",
+                "newdoc id = doc1"
+            ]
+        );
+        assert_eq!(g.metadata().get("sent_id"), Some(&"2"));
+        assert_eq!(g.metadata().get("newdoc id"), Some(&"doc1"));
+
+        assert_eq!(
+            g.to_string(),
+            "# sent_id = 2\n# a comment\n# text=Hallo Welt\n# newdoc id = doc1\n\
+             1\tHallo\t_\t_\t_\t_\t_\t_\t_\t_\n\
+             2\tWelt\t_\t_\t_\t_\t_\t_\t_\t_\n"
+        );
+    }
+
     #[test]
     fn dependents() {
         let mut g = Sentence::default();
@@ -622,6 +2093,129 @@ mod tests {
         assert!(g.dep_graph().dependents(3).next().is_none());
     }
 
+    #[test]
+    fn empty_nodes() {
+        let mut g: Sentence = vec!["Sue", "likes", "coffee", "and", "Bill", "tea"]
+            .into_iter()
+            .map(Token::new)
+            .collect();
+
+        let g2 = g.clone();
+
+        assert_eq!(
+            g.add_empty_node(5, Token::new("likes")),
+            NodeId::Empty(5, 1)
+        );
+        assert_eq!(
+            g.add_empty_node(5, Token::new("drinks")),
+            NodeId::Empty(5, 2)
+        );
+        assert_eq!(
+            g.add_empty_node(0, Token::new("begin")),
+            NodeId::Empty(0, 1)
+        );
+        assert_eq!(g.len(), 7);
+        assert_ne!(g, g2);
+
+        let ids = g.empty_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            &[
+                NodeId::Empty(0, 1),
+                NodeId::Empty(5, 1),
+                NodeId::Empty(5, 2)
+            ]
+        );
+
+        assert_eq!(
+            g.node(NodeId::Empty(5, 2)),
+            Some(&Node::Empty(Token::new("drinks")))
+        );
+        assert_eq!(
+            g.node(NodeId::Token(5)),
+            Some(&Node::Token(Token::new("Bill")))
+        );
+        assert!(g.node(NodeId::Empty(5, 3)).is_none());
+        assert!(g.node(NodeId::Token(7)).is_none());
+
+        assert!(NodeId::Token(5) < NodeId::Empty(5, 1));
+        assert!(NodeId::Empty(5, 2) < NodeId::Token(6));
+        assert_eq!(NodeId::Empty(5, 2).to_string(), "5.2");
+    }
+
+    #[test]
+    fn enhanced_deprels() {
+        let mut g: Sentence = vec!["Sue", "likes", "coffee", "and", "Bill", "tea"]
+            .into_iter()
+            .map(Token::new)
+            .collect();
+        let likes = g.add_empty_node(5, Token::new("likes"));
+
+        let g2 = g.clone();
+
+        let mut enhanced = g.enhanced_dep_graph_mut();
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(0, Some("root"), 2)));
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(2, Some("nsubj"), 1)));
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(likes, Some("nsubj"), 1)));
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(likes, Some("nsubj"), 5)));
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(2, Some("conj"), likes)));
+        assert!(!enhanced.add_deprel(EnhancedDepTriple::new(2, Some("conj"), likes)));
+
+        // Cycles are permitted.
+        assert!(enhanced.add_deprel(EnhancedDepTriple::new(1, Some("acl"), 2)));
+
+        assert_ne!(g, g2);
+
+        let enhanced = g.enhanced_dep_graph();
+        assert_eq!(
+            enhanced.heads(1).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(2, Some("nsubj"), 1),
+                EnhancedDepTriple::new(likes, Some("nsubj"), 1)
+            ]
+        );
+        assert_eq!(
+            enhanced.heads(2).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(0, Some("root"), 2),
+                EnhancedDepTriple::new(1, Some("acl"), 2)
+            ]
+        );
+        assert_eq!(
+            enhanced.dependents(likes).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(likes, Some("nsubj"), 1),
+                EnhancedDepTriple::new(likes, Some("nsubj"), 5)
+            ]
+        );
+        assert_eq!(enhanced.triples().count(), 6);
+        assert!(enhanced.heads(3).next().is_none());
+
+        // The basic dependency graph is not affected.
+        assert!(g.dep_graph().head(1).is_none());
+
+        let mut enhanced = g.enhanced_dep_graph_mut();
+        assert!(enhanced.remove_deprel(&EnhancedDepTriple::new(1, Some("acl"), 2)));
+        assert!(!enhanced.remove_deprel(&EnhancedDepTriple::new(1, Some("acl"), 2)));
+        assert_eq!(
+            enhanced.remove_head_rels(1),
+            &[
+                EnhancedDepTriple::new(2, Some("nsubj".to_owned()), 1),
+                EnhancedDepTriple::new(likes, Some("nsubj".to_owned()), 1)
+            ]
+        );
+        assert!(enhanced.heads(1).next().is_none());
+        assert_eq!(enhanced.heads(2).count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn enhanced_deprel_out_of_bounds() {
+        let mut g: Sentence = vec!["a", "b"].into_iter().map(Token::new).collect();
+        g.enhanced_dep_graph_mut()
+            .add_deprel(EnhancedDepTriple::new(NodeId::Empty(1, 1), Some("dep"), 2));
+    }
+
     #[test]
     fn equality() {
         let mut g1 = Sentence::default();
@@ -657,6 +2251,48 @@ mod tests {
         assert_ne!(g1, g4);
     }
 
+    #[test]
+    fn multiword_tokens() {
+        let mut g: Sentence = vec!["Ich", "gehe", "zu", "dem", "Bahnhof", "in", "dem", "Zug"]
+            .into_iter()
+            .map(Token::new)
+            .collect();
+        g.add_multiword_token(MultiwordToken::new(6, 7, "im"));
+        g.add_multiword_token(MultiwordToken::new(3, 4, "zum"));
+
+        assert_eq!(
+            g.multiword_tokens(),
+            &[
+                MultiwordToken::new(3, 4, "zum"),
+                MultiwordToken::new(6, 7, "im")
+            ]
+        );
+
+        for &idx in &[0, 1, 2, 5, 8] {
+            assert!(g.multiword_token(idx).is_none());
+        }
+
+        assert_eq!(g.multiword_token(3).map(MultiwordToken::form), Some("zum"));
+        assert_eq!(g.multiword_token(4).map(MultiwordToken::form), Some("zum"));
+        assert_eq!(g.multiword_token(7).map(MultiwordToken::form), Some("im"));
+
+        let g2 = g.clone();
+        assert_eq!(
+            g.remove_multiword_token(4),
+            Some(MultiwordToken::new(3, 4, "zum"))
+        );
+        assert!(g.multiword_token(3).is_none());
+        assert_ne!(g, g2);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn multiword_tokens_overlap() {
+        let mut g: Sentence = vec!["a", "b", "c"].into_iter().map(Token::new).collect();
+        g.add_multiword_token(MultiwordToken::new(1, 2, "ab"));
+        g.add_multiword_token(MultiwordToken::new(2, 3, "bc"));
+    }
+
     #[test]
     fn remove_deprel() {
         let mut g = Sentence::default();
@@ -680,4 +2316,489 @@ mod tests {
             Some(DepTriple::new(0, Some("root"), 2))
         );
     }
+
+    #[test]
+    fn subtrees() {
+        let mut sentence = read_sentences("testdata/nonprojective.conll").remove(0);
+        let graph = sentence.dep_graph();
+
+        assert_eq!(graph.descendants(8), vec![1, 2, 3, 7]);
+        assert_eq!(graph.token_yield(8), vec![1, 2, 3, 7, 8]);
+        assert_eq!(graph.span(8), (1, 8));
+        assert!(!graph.is_contiguous(8));
+
+        assert_eq!(graph.token_yield(1), vec![1, 2, 3]);
+        assert!(graph.is_contiguous(1));
+
+        assert!(graph.descendants(10).is_empty());
+        assert_eq!(graph.span(10), (10, 10));
+        assert!(graph.is_contiguous(10));
+
+        assert_eq!(graph.token_yield(0), (0..=10).collect::<Vec<_>>());
+        assert_eq!(graph.depth(0), Some(0));
+        assert_eq!(graph.depth(4), Some(1));
+        assert_eq!(graph.depth(2), Some(6));
+
+        // The views share their implementation, check the mutable view
+        // on a graph with a cycle.
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("cycle"), 1));
+        assert_eq!(graph.descendants(1), vec![2, 3]);
+        assert_eq!(graph.token_yield(2), vec![1, 2, 3]);
+        assert_eq!(graph.span(3), (1, 3));
+        assert!(graph.is_contiguous(3));
+        assert_eq!(graph.depth(1), None);
+        assert_eq!(graph.depth(9), Some(2));
+    }
+
+    #[test]
+    fn paths() {
+        let mut sentence = read_sentences("testdata/nonprojective.conll").remove(0);
+        let graph = sentence.dep_graph();
+
+        assert_eq!(graph.lowest_common_ancestor(1, 7), Some(8));
+        assert_eq!(graph.lowest_common_ancestor(2, 10), Some(9));
+        assert_eq!(graph.lowest_common_ancestor(2, 1), Some(1));
+        assert_eq!(graph.lowest_common_ancestor(3, 3), Some(3));
+
+        assert_eq!(
+            graph.path(2, 7),
+            Some(vec![
+                (PathDirection::Up, DepTriple::new(3, Some("DET"), 2)),
+                (PathDirection::Up, DepTriple::new(1, Some("PN"), 3)),
+                (PathDirection::Up, DepTriple::new(8, Some("PP"), 1)),
+                (PathDirection::Down, DepTriple::new(8, Some("DET"), 7)),
+            ])
+        );
+        assert_eq!(
+            graph.path(8, 3),
+            Some(vec![
+                (PathDirection::Down, DepTriple::new(8, Some("PP"), 1)),
+                (PathDirection::Down, DepTriple::new(1, Some("PN"), 3)),
+            ])
+        );
+        assert_eq!(graph.path(5, 5), Some(vec![]));
+
+        let ancestors = Ancestors::new(&graph);
+        for token1 in 0..graph.len() {
+            for token2 in 0..graph.len() {
+                assert_eq!(
+                    ancestors.lowest_common_ancestor(token1, token2),
+                    graph.lowest_common_ancestor(token1, token2)
+                );
+                assert_eq!(ancestors.path(token1, token2), graph.path(token1, token2));
+            }
+        }
+
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("cycle"), 1));
+        assert_eq!(graph.lowest_common_ancestor(1, 9), None);
+        assert_eq!(graph.path(9, 1), None);
+        assert_eq!(graph.lowest_common_ancestor(10, 5), Some(4));
+        assert_eq!(
+            Ancestors::new(&sentence.dep_graph()).lowest_common_ancestor(10, 5),
+            Some(4)
+        );
+    }
+
+    fn reorder_sentence() -> Sentence {
+        let mut g: Sentence = vec!["Ich", "gehe", "zu", "dem", "Bahnhof", "."]
+            .into_iter()
+            .map(Token::new)
+            .collect();
+        g.add_multiword_token(MultiwordToken::new(3, 4, "zum"));
+        let empty = g.add_empty_node(2, Token::new("fahre"));
+
+        let mut graph = g.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("nsubj"), 1));
+        graph.add_deprel(DepTriple::new(0, Some("root"), 2));
+        graph.add_deprel(DepTriple::new(5, Some("case"), 3));
+        graph.add_deprel(DepTriple::new(5, Some("det"), 4));
+        graph.add_deprel(DepTriple::new(2, Some("obl"), 5));
+        graph.add_deprel(DepTriple::new(2, Some("punct"), 6));
+
+        let mut graph = g.proj_dep_graph_mut();
+        graph.add_deprel(DepTriple::new(0, Some("root"), 2));
+        graph.add_deprel(DepTriple::new(2, Some("punct"), 6));
+
+        let mut enhanced = g.enhanced_dep_graph_mut();
+        enhanced.add_deprel(EnhancedDepTriple::new(0, Some("root"), 2));
+        enhanced.add_deprel(EnhancedDepTriple::new(2, Some("nsubj"), 1));
+        enhanced.add_deprel(EnhancedDepTriple::new(empty, Some("nsubj"), 1));
+        enhanced.add_deprel(EnhancedDepTriple::new(2, Some("conj"), empty));
+        enhanced.add_deprel(EnhancedDepTriple::new(2, Some("punct"), 6));
+
+        g
+    }
+
+    fn forms(sentence: &Sentence) -> Vec<&str> {
+        (1..sentence.len())
+            .map(|idx| sentence[idx].token().unwrap().form())
+            .collect()
+    }
+
+    fn heads(sentence: &Sentence, proj: bool) -> Vec<Option<(usize, String)>> {
+        let graph = if proj {
+            sentence.proj_dep_graph()
+        } else {
+            sentence.dep_graph()
+        };
+        (1..sentence.len())
+            .map(|idx| {
+                graph
+                    .head(idx)
+                    .map(|triple| (triple.head(), triple.relation().unwrap().to_owned()))
+            })
+            .collect()
+    }
+
+    fn head(head: usize, relation: &str) -> Option<(usize, String)> {
+        Some((head, relation.to_owned()))
+    }
+
+    #[test]
+    fn insert() {
+        let mut g = reorder_sentence();
+        g.insert(1, Token::new("Morgen"));
+        assert_eq!(
+            forms(&g),
+            &["Morgen", "Ich", "gehe", "zu", "dem", "Bahnhof", "."]
+        );
+        assert_eq!(
+            heads(&g, false),
+            &[
+                None,
+                head(3, "nsubj"),
+                head(0, "root"),
+                head(6, "case"),
+                head(6, "det"),
+                head(3, "obl"),
+                head(3, "punct")
+            ]
+        );
+        assert_eq!(
+            heads(&g, true),
+            &[
+                None,
+                None,
+                head(0, "root"),
+                None,
+                None,
+                None,
+                head(3, "punct")
+            ]
+        );
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(4, 5, "zum")]);
+        assert_eq!(
+            g.empty_nodes().map(|(id, _)| id).collect::<Vec<_>>(),
+            &[NodeId::Empty(3, 1)]
+        );
+        assert_eq!(
+            g.enhanced_dep_graph().dependents(3).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(3, Some("nsubj"), 2),
+                EnhancedDepTriple::new(3, Some("conj"), NodeId::Empty(3, 1)),
+                EnhancedDepTriple::new(3, Some("punct"), 7),
+            ]
+        );
+
+        // Insertion in a multiword token removes the multiword token.
+        g.insert(5, Token::new("Berliner"));
+        assert!(g.multiword_tokens().is_empty());
+
+        // Insertion after the last token.
+        let mut g = reorder_sentence();
+        g.insert(7, Token::new("!"));
+        assert_eq!(forms(&g)[5..], [".", "!"]);
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(3, 4, "zum")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "root")]
+    fn insert_before_root() {
+        reorder_sentence().insert(0, Token::new("Morgen"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut g = reorder_sentence();
+        assert_eq!(g.remove(6, RemovalPolicy::Detach), Token::new("."));
+        assert_eq!(forms(&g), &["Ich", "gehe", "zu", "dem", "Bahnhof"]);
+        assert_eq!(heads(&g, true), &[None, head(0, "root"), None, None, None]);
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(3, 4, "zum")]);
+        assert_eq!(g.enhanced_dep_graph().triples().count(), 4);
+
+        // Detach the dependents of a token.
+        let mut g = reorder_sentence();
+        g.remove(5, RemovalPolicy::Detach);
+        assert_eq!(
+            heads(&g, false),
+            &[
+                head(2, "nsubj"),
+                head(0, "root"),
+                None,
+                None,
+                head(2, "punct")
+            ]
+        );
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(3, 4, "zum")]);
+
+        // Reattach the dependents of a token to its head.
+        let mut g = reorder_sentence();
+        g.remove(2, RemovalPolicy::Reattach);
+        assert_eq!(forms(&g), &["Ich", "zu", "dem", "Bahnhof", "."]);
+        assert_eq!(
+            heads(&g, false),
+            &[
+                head(0, "nsubj"),
+                head(4, "case"),
+                head(4, "det"),
+                head(0, "obl"),
+                head(0, "punct")
+            ]
+        );
+        assert_eq!(heads(&g, true), &[None, None, None, None, head(0, "punct")]);
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(2, 3, "zum")]);
+
+        // The empty node follows the preceding token.
+        assert_eq!(
+            g.enhanced_dep_graph().triples().collect::<Vec<_>>(),
+            &[EnhancedDepTriple::new(
+                NodeId::Empty(1, 1),
+                Some("nsubj"),
+                1
+            )]
+        );
+
+        // Removal of a token in a multiword token.
+        g.remove(2, RemovalPolicy::Reattach);
+        assert!(g.multiword_tokens().is_empty());
+        assert_eq!(heads(&g, false)[1], head(3, "det"));
+    }
+
+    #[test]
+    fn permute() {
+        let mut g = reorder_sentence();
+        g.permute(&[2, 1, 3, 4, 5, 6]);
+        assert_eq!(forms(&g), &["gehe", "Ich", "zu", "dem", "Bahnhof", "."]);
+        assert_eq!(
+            heads(&g, false),
+            &[
+                head(0, "root"),
+                head(1, "nsubj"),
+                head(5, "case"),
+                head(5, "det"),
+                head(1, "obl"),
+                head(1, "punct")
+            ]
+        );
+        assert_eq!(
+            heads(&g, true),
+            &[head(0, "root"), None, None, None, None, head(1, "punct")]
+        );
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(3, 4, "zum")]);
+        assert_eq!(
+            g.enhanced_dep_graph().heads(2).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(1, Some("nsubj"), 2),
+                EnhancedDepTriple::new(NodeId::Empty(1, 1), Some("nsubj"), 2),
+            ]
+        );
+
+        // Tokens of a multiword token that are no longer adjacent.
+        g.permute(&[1, 2, 4, 3, 5, 6]);
+        assert!(g.multiword_tokens().is_empty());
+        assert_eq!(heads(&g, false)[2], head(5, "det"));
+    }
+
+    #[test]
+    #[should_panic(expected = "permutation")]
+    fn permute_invalid() {
+        reorder_sentence().permute(&[1, 2, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut g = reorder_sentence();
+        g.add_comment("sent_id = 1");
+        g.add_comment("text = Ich gehe  zum Bahnhof.");
+        let second = g.split_off(3, SplitPolicy::AttachToRoot);
+
+        assert_eq!(forms(&g), &["Ich", "gehe"]);
+        assert_eq!(heads(&g, false), &[head(2, "nsubj"), head(0, "root")]);
+        assert_eq!(heads(&g, true), &[None, head(0, "root")]);
+        assert!(g.multiword_tokens().is_empty());
+        assert_eq!(
+            g.empty_nodes().map(|(id, _)| id).collect::<Vec<_>>(),
+            &[NodeId::Empty(2, 1)]
+        );
+        assert_eq!(g.enhanced_dep_graph().triples().count(), 4);
+        assert_eq!(g.comments(), &["sent_id = 1", "text = Ich gehe"]);
+
+        assert_eq!(forms(&second), &["zu", "dem", "Bahnhof", "."]);
+        assert_eq!(
+            heads(&second, false),
+            &[
+                head(3, "case"),
+                head(3, "det"),
+                head(0, "obl"),
+                head(0, "punct")
+            ]
+        );
+        assert_eq!(heads(&second, true), &[None, None, None, head(0, "punct")]);
+        assert_eq!(
+            second.multiword_tokens(),
+            &[MultiwordToken::new(1, 2, "zum")]
+        );
+        assert_eq!(second.empty_nodes().count(), 0);
+        assert_eq!(
+            second.enhanced_dep_graph().triples().collect::<Vec<_>>(),
+            &[EnhancedDepTriple::new(0, Some("punct"), 4)]
+        );
+        assert_eq!(second.comments(), &["text = zum Bahnhof."]);
+
+        // Remove crossing relations.
+        let mut g = reorder_sentence();
+        g.add_comment("text = Ich gehe zum Bahnhof.");
+        let second = g.split_off(4, SplitPolicy::Detach);
+        assert_eq!(forms(&g), &["Ich", "gehe", "zu"]);
+        assert_eq!(heads(&g, false), &[head(2, "nsubj"), head(0, "root"), None]);
+        assert_eq!(heads(&second, false), &[head(2, "det"), None, None]);
+        assert!(g.multiword_tokens().is_empty());
+        assert!(second.multiword_tokens().is_empty());
+        assert_eq!(second.enhanced_dep_graph().triples().count(), 0);
+
+        // The text cannot be split within a multiword token.
+        assert!(g.comments().is_empty());
+        assert!(second.comments().is_empty());
+
+        // Split after the last token.
+        let mut g = reorder_sentence();
+        let second = g.split_off(7, SplitPolicy::Detach);
+        assert_eq!(g, reorder_sentence());
+        assert_eq!(second, Sentence::new());
+    }
+
+    #[test]
+    fn append() {
+        let mut g: Sentence = vec!["Ich", "gehe", "."]
+            .into_iter()
+            .map(Token::new)
+            .collect();
+        g.add_comment("a");
+        g.add_empty_node(3, Token::new("laufe"));
+        let mut graph = g.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("nsubj"), 1));
+        graph.add_deprel(DepTriple::new(0, Some("root"), 2));
+        graph.add_deprel(DepTriple::new(2, Some("punct"), 3));
+        g.proj_dep_graph_mut()
+            .add_deprel(DepTriple::new(0, Some("root"), 2));
+        g.enhanced_dep_graph_mut()
+            .add_deprel(EnhancedDepTriple::new(0, Some("root"), 2));
+
+        let mut other = reorder_sentence();
+        other.add_comment("b");
+        other.add_empty_node(0, Token::new("und"));
+
+        g.append(other, Some("parataxis"));
+
+        assert_eq!(
+            forms(&g),
+            &["Ich", "gehe", ".", "Ich", "gehe", "zu", "dem", "Bahnhof", "."]
+        );
+        assert_eq!(
+            heads(&g, false),
+            &[
+                head(2, "nsubj"),
+                head(0, "root"),
+                head(2, "punct"),
+                head(5, "nsubj"),
+                head(2, "parataxis"),
+                head(8, "case"),
+                head(8, "det"),
+                head(5, "obl"),
+                head(5, "punct")
+            ]
+        );
+        assert_eq!(
+            heads(&g, true),
+            &[
+                None,
+                head(0, "root"),
+                None,
+                None,
+                head(2, "parataxis"),
+                None,
+                None,
+                None,
+                head(5, "punct")
+            ]
+        );
+        assert_eq!(g.multiword_tokens(), &[MultiwordToken::new(6, 7, "zum")]);
+        assert_eq!(
+            g.empty_nodes().map(|(id, _)| id).collect::<Vec<_>>(),
+            &[
+                NodeId::Empty(3, 1),
+                NodeId::Empty(3, 2),
+                NodeId::Empty(5, 1)
+            ]
+        );
+        assert_eq!(
+            g.enhanced_dep_graph().heads(5).collect::<Vec<_>>(),
+            &[EnhancedDepTriple::new(2, Some("parataxis"), 5)]
+        );
+        assert_eq!(
+            g.enhanced_dep_graph().heads(4).collect::<Vec<_>>(),
+            &[
+                EnhancedDepTriple::new(5, Some("nsubj"), 4),
+                EnhancedDepTriple::new(NodeId::Empty(5, 1), Some("nsubj"), 4)
+            ]
+        );
+        assert_eq!(g.comments(), &["a", "b"]);
+
+        // Without root dependents, the relations to the root are kept.
+        let mut g = Sentence::new();
+        g.append(reorder_sentence(), Some("parataxis"));
+        assert_eq!(g, reorder_sentence());
+    }
+
+    #[test]
+    fn append_metadata() {
+        let mut g: Sentence = vec!["Ich", "gehe"].into_iter().map(Token::new).collect();
+        g.add_comment("sent_id = 1");
+        g.add_comment("text = Ich gehe");
+        g.dep_graph_mut()
+            .add_deprel(DepTriple::new(0, Some("root"), 2));
+
+        let mut other: Sentence = vec!["zum", "Bahnhof"].into_iter().map(Token::new).collect();
+        other.add_comment("sent_id = 2");
+        other.add_comment("newpar");
+        other.add_comment("text = zum Bahnhof");
+
+        g.append(other, Some("obl"));
+
+        assert_eq!(
+            g.comments(),
+            &["sent_id = 1", "text = Ich gehe zum Bahnhof", "newpar"]
+        );
+        assert_eq!(g.metadata()["sent_id"], "1");
+
+        g.set_metadata("sent_id", "1-2");
+        assert_eq!(g.metadata()["sent_id"], "1-2");
+
+        // Without a text in the appended sentence, the text is removed.
+        let mut other: Sentence = vec!["."].into_iter().map(Token::new).collect();
+        other.add_comment("sent_id = 3");
+        g.append(other, Some("punct"));
+        assert_eq!(g.comments(), &["sent_id = 1-2", "newpar"]);
+    }
+
+    #[test]
+    fn split_off_append_round_trip() {
+        let mut g = reorder_sentence();
+        let second = g.split_off(6, SplitPolicy::AttachToRoot);
+        assert_ne!(g, reorder_sentence());
+        g.append(second, Some("punct"));
+        assert_eq!(g, reorder_sentence());
+    }
 }
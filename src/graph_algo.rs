@@ -72,7 +72,7 @@ mod tests {
     use crate::BfsWithDepth;
 
     lazy_static! {
-    static ref GRAPH_DEPTH_4: Graph<(), usize> = Graph::<(), usize>::from_edges(&[
+    static ref GRAPH_DEPTH_4: Graph<(), usize> = Graph::<(), usize>::from_edges([
         (0, 1), (0, 2), (0, 3),
         (1, 4), (1, 5), (5, 6),
         (3, 7), (3, 8)
@@ -99,5 +99,4 @@ mod tests {
             .collect();
         assert_eq!(*GRAPH_DEPTH_4_BFS_WITH_DEPTH, visits);
     }
-
 }
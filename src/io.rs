@@ -2,9 +2,9 @@
 
 use std::io;
 
-use failure::Error;
+use crate::error::Error;
 
-use crate::error::ReadError;
+use crate::error::{ReadError, ReadErrorKind};
 use crate::graph::{DepTriple, Sentence};
 use crate::token::{Features, Token, EMPTY_TOKEN};
 
@@ -29,14 +29,49 @@ pub trait ReadSentence {
 
 /// A reader for CoNLL-X sentences.
 pub struct Reader<R> {
-    read: R,
+    lines: LineReader<R>,
+    strict: bool,
 }
 
 impl<R: io::BufRead> Reader<R> {
     /// Construct a new reader from an object that implements the
     /// `io::BufRead` trait.
     pub fn new(read: R) -> Reader<R> {
-        Reader { read }
+        Reader {
+            lines: LineReader::new(read),
+            strict: false,
+        }
+    }
+
+    /// Enable or disable strict reading.
+    ///
+    /// In strict mode, the reader validates that:
+    ///
+    /// * token identifiers are sequential, starting at 1;
+    /// * every token line has exactly ten columns;
+    /// * columns are separated by a single tab and do not contain
+    ///   whitespace.
+    ///
+    /// Heads that are not in the sentence are rejected in both modes.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Enable or disable lenient reading.
+    ///
+    /// In lenient mode, a sentence that cannot be read is skipped and
+    /// reading resumes after the next blank line. The errors of skipped
+    /// sentences are available through `skipped`. I/O errors are always
+    /// returned.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lines.lenient = lenient;
+        self
+    }
+
+    /// Get the errors of the sentences that were skipped in lenient mode.
+    pub fn skipped(&self) -> &[ReadError] {
+        &self.lines.skipped
     }
 }
 
@@ -51,25 +86,37 @@ impl<R: io::BufRead> IntoIterator for Reader<R> {
 
 impl<R: io::BufRead> ReadSentence for Reader<R> {
     fn read_sentence(&mut self) -> Result<Option<Sentence>, Error> {
-        let mut line = String::new();
+        loop {
+            match self.try_read_sentence() {
+                Err(err) => self.lines.recover(err)?,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: io::BufRead> Reader<R> {
+    fn try_read_sentence(&mut self) -> Result<Option<Sentence>, Error> {
         let mut sentence = Sentence::new();
         let mut edges = Vec::new();
         let mut proj_edges = Vec::new();
 
-        loop {
-            line.clear();
+        self.lines.start_sentence();
 
+        loop {
             // End of reader.
-            if self.read.read_line(&mut line)? == 0 {
+            if !self.lines.next_line()? {
                 if sentence.len() == 1 {
                     return Ok(None);
                 }
 
-                add_edges(&mut sentence, edges, proj_edges);
+                add_edges(&self.lines, &mut sentence, edges, proj_edges)?;
 
                 return Ok(Some(sentence));
             }
 
+            let line = self.lines.current();
+
             // The blank line is a sentence separator. We want to be robust
             // in the case a CoNLL file is malformed and has two newlines as
             // a separator.
@@ -78,57 +125,235 @@ impl<R: io::BufRead> ReadSentence for Reader<R> {
                     continue;
                 }
 
-                add_edges(&mut sentence, edges, proj_edges);
+                add_edges(&self.lines, &mut sentence, edges, proj_edges)?;
 
                 return Ok(Some(sentence));
             }
 
-            let mut iter = line.trim().split_terminator('\t');
+            // Comments are only permitted before the first token.
+            if sentence.len() == 1 && line.starts_with('#') {
+                sentence.add_comment(line[1..].trim());
+                continue;
+            }
 
-            parse_identifier_field(iter.next())?;
+            let line_number = self.lines.line_number();
+            parse_line(
+                line,
+                line_number,
+                self.strict,
+                &mut sentence,
+                &mut edges,
+                &mut proj_edges,
+            )
+            .map_err(|kind| self.lines.error(kind))?;
+        }
+    }
+}
 
-            let mut token = Token::new(parse_form_field(iter.next())?);
-            token.set_lemma(parse_string_field(iter.next()));
-            token.set_cpos(parse_string_field(iter.next()));
-            token.set_pos(parse_string_field(iter.next()));
-            token.set_features(parse_string_field(iter.next()).map(|s| Features::from(s.as_str())));
+/// Parse a CoNLL-X token line and add the token to the sentence.
+///
+/// The dependency relations are paired with the line number, so that
+/// heads can be validated when the sentence is complete.
+fn parse_line(
+    line: &str,
+    line_number: usize,
+    strict: bool,
+    sentence: &mut Sentence,
+    edges: &mut Vec<(usize, DepTriple<String>)>,
+    proj_edges: &mut Vec<(usize, DepTriple<String>)>,
+) -> Result<(), ReadErrorKind> {
+    if strict {
+        validate_columns(line)?;
+    }
 
-            // Head relation.
-            if let Some(head) = parse_numeric_field(iter.next())? {
-                let head_rel = parse_string_field(iter.next());
-                edges.push(DepTriple::new(head, head_rel, sentence.len()));
-            }
+    let mut iter = line.trim().split_terminator('\t');
 
-            // Projective head relation.
-            if let Some(proj_head) = parse_numeric_field(iter.next())? {
-                let proj_head_rel = parse_string_field(iter.next());
-                proj_edges.push(DepTriple::new(proj_head, proj_head_rel, sentence.len()));
-            }
+    let id = parse_identifier_field(iter.next())?;
+    if strict && id != Some(sentence.len()) {
+        return Err(ReadErrorKind::NonSequentialIdentifier {
+            value: format!("{} (expected {})", id.unwrap_or(0), sentence.len()),
+        });
+    }
 
-            //token.set_head();
-            //token.set_head_rel();
-            //token.set_p_head(parse_numeric_field(iter.next())?);
-            //token.set_p_head_rel(parse_string_field(iter.next()));
+    let mut token = Token::new(parse_form_field(iter.next())?);
+    token.set_lemma(parse_string_field(iter.next()));
+    token.set_cpos(parse_string_field(iter.next()));
+    token.set_pos(parse_string_field(iter.next()));
+    token.set_features(parse_string_field(iter.next()).map(|s| Features::from(s.as_str())));
+
+    // Head relation.
+    let head = parse_numeric_field(iter.next())?;
+    let head_rel = parse_string_field(iter.next());
+    if let Some(head) = head {
+        edges.push((line_number, DepTriple::new(head, head_rel, sentence.len())));
+    }
+
+    // Projective head relation.
+    let proj_head = parse_numeric_field(iter.next())?;
+    let proj_head_rel = parse_string_field(iter.next());
+    if let Some(proj_head) = proj_head {
+        proj_edges.push((
+            line_number,
+            DepTriple::new(proj_head, proj_head_rel, sentence.len()),
+        ));
+    }
+
+    sentence.push(token);
+
+    Ok(())
+}
+
+/// Validate the columns of a token line.
+fn validate_columns(line: &str) -> Result<(), ReadErrorKind> {
+    let line = line.trim_end_matches(&['\r', '\n'][..]);
 
-            sentence.push(token);
+    for field in line.split('\t') {
+        if field.is_empty() || field.contains(char::is_whitespace) {
+            return Err(ReadErrorKind::InvalidSeparator {
+                value: field.to_owned(),
+            });
         }
     }
+
+    let n_columns = line.split('\t').count();
+    if n_columns != 10 {
+        return Err(ReadErrorKind::InvalidColumnCount {
+            value: format!("{} (expected 10)", n_columns),
+        });
+    }
+
+    Ok(())
 }
 
-fn add_edges(
-    sentence: &mut Sentence,
-    edges: Vec<DepTriple<String>>,
-    proj_edges: Vec<DepTriple<String>>,
-) {
-    for edge in edges {
-        sentence.dep_graph_mut().add_deprel(edge);
+/// A line reader that keeps track of the position in the input.
+///
+/// The lines of the sentence that is being read are retained, so that
+/// errors that are only detected after the sentence is read completely
+/// can still refer to the offending line.
+pub(crate) struct LineReader<R> {
+    read: R,
+    lines: Vec<String>,
+    first_line: usize,
+    sentence: usize,
+    pub(crate) lenient: bool,
+    pub(crate) skipped: Vec<ReadError>,
+}
+
+impl<R: io::BufRead> LineReader<R> {
+    pub(crate) fn new(read: R) -> Self {
+        LineReader {
+            read,
+            lines: Vec::new(),
+            first_line: 1,
+            sentence: 0,
+            lenient: false,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Recover from an error.
+    ///
+    /// In lenient mode, read errors are recorded and the remaining lines
+    /// of the offending sentence are skipped. Otherwise, the error is
+    /// returned.
+    pub(crate) fn recover(&mut self, err: Error) -> Result<(), Error> {
+        let err = match err {
+            Error::Read(err) if self.lenient => err,
+            err => return Err(err),
+        };
+
+        // Errors that are detected after reading a sentence completely
+        // are reported at the sentence separator.
+        while !self.current().trim().is_empty() && self.next_line()? {}
+
+        self.skipped.push(err);
+
+        Ok(())
+    }
+
+    /// Start reading the next sentence.
+    pub(crate) fn start_sentence(&mut self) {
+        self.first_line += self.lines.len();
+        self.lines.clear();
+        self.sentence += 1;
+    }
+
+    /// Read the next line. Returns `false` at the end of the input.
+    pub(crate) fn next_line(&mut self) -> io::Result<bool> {
+        let mut line = String::new();
+        if self.read.read_line(&mut line)? == 0 {
+            return Ok(false);
+        }
+
+        self.lines.push(line);
+
+        Ok(true)
     }
 
-    for edge in proj_edges {
-        sentence.proj_dep_graph_mut().add_deprel(edge);
+    /// Get the line that was read last.
+    pub(crate) fn current(&self) -> &str {
+        self.lines.last().map(String::as_str).unwrap_or("")
+    }
+
+    /// Get the number of the line that was read last.
+    pub(crate) fn line_number(&self) -> usize {
+        self.first_line + self.lines.len() - 1
+    }
+
+    /// Construct an error for the line that was read last.
+    pub(crate) fn error(&self, kind: ReadErrorKind) -> ReadError {
+        self.error_at(self.line_number(), kind)
+    }
+
+    /// Construct an error for a line of the current sentence.
+    pub(crate) fn error_at(&self, line: usize, kind: ReadErrorKind) -> ReadError {
+        let content = self
+            .lines
+            .get(line - self.first_line)
+            .map(|l| l.trim_end_matches(&['\r', '\n'][..]))
+            .unwrap_or("");
+        ReadError::new(line, self.sentence, content, kind)
     }
 }
 
+/// Add dependency relations to a sentence.
+///
+/// The relations are paired with the numbers of the lines that they
+/// were read from. An error is returned when a head is not in the
+/// sentence.
+pub(crate) fn add_edges<R>(
+    lines: &LineReader<R>,
+    sentence: &mut Sentence,
+    edges: Vec<(usize, DepTriple<String>)>,
+    proj_edges: Vec<(usize, DepTriple<String>)>,
+) -> Result<(), ReadError>
+where
+    R: io::BufRead,
+{
+    for (proj, (line, edge)) in edges
+        .into_iter()
+        .map(|edge| (false, edge))
+        .chain(proj_edges.into_iter().map(|edge| (true, edge)))
+    {
+        if edge.head() >= sentence.len() {
+            return Err(lines.error_at(
+                line,
+                ReadErrorKind::HeadOutOfBounds {
+                    value: edge.head().to_string(),
+                },
+            ));
+        }
+
+        if proj {
+            sentence.proj_dep_graph_mut().add_deprel(edge);
+        } else {
+            sentence.dep_graph_mut().add_deprel(edge);
+        }
+    }
+
+    Ok(())
+}
+
 /// An iterator over the sentences in a `Reader`.
 pub struct Sentences<R>
 where
@@ -137,6 +362,21 @@ where
     reader: R,
 }
 
+impl<R> Sentences<R>
+where
+    R: ReadSentence,
+{
+    /// Borrow the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Unwrap the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
 impl<R> Iterator for Sentences<R>
 where
     R: ReadSentence,
@@ -152,11 +392,13 @@ where
     }
 }
 
-fn parse_form_field(field: Option<&str>) -> Result<String, ReadError> {
-    field.map(str::to_owned).ok_or(ReadError::MissingFormField)
+pub(crate) fn parse_form_field(field: Option<&str>) -> Result<String, ReadErrorKind> {
+    field
+        .map(str::to_owned)
+        .ok_or(ReadErrorKind::MissingFormField)
 }
 
-fn parse_string_field(field: Option<&str>) -> Option<String> {
+pub(crate) fn parse_string_field(field: Option<&str>) -> Option<String> {
     field.and_then(|s| {
         if s == EMPTY_TOKEN {
             None
@@ -166,20 +408,20 @@ fn parse_string_field(field: Option<&str>) -> Option<String> {
     })
 }
 
-fn parse_identifier_field(field: Option<&str>) -> Result<Option<usize>, ReadError> {
+pub(crate) fn parse_identifier_field(field: Option<&str>) -> Result<Option<usize>, ReadErrorKind> {
     match field {
-        None => Err(ReadError::ParseIdentifierField {
+        None => Err(ReadErrorKind::ParseIdentifierField {
             value: "A token identifier should be present".to_owned(),
         }),
         Some(s) => {
             if s == EMPTY_TOKEN {
-                return Err(ReadError::ParseIdentifierField {
+                return Err(ReadErrorKind::ParseIdentifierField {
                     value: s.to_owned(),
                 });
             }
 
             Ok(Some(s.parse::<usize>().map_err(|_| {
-                ReadError::ParseIntField {
+                ReadErrorKind::ParseIntField {
                     value: s.to_owned(),
                 }
             })?))
@@ -187,7 +429,7 @@ fn parse_identifier_field(field: Option<&str>) -> Result<Option<usize>, ReadErro
     }
 }
 
-fn parse_numeric_field(field: Option<&str>) -> Result<Option<usize>, ReadError> {
+pub(crate) fn parse_numeric_field(field: Option<&str>) -> Result<Option<usize>, ReadErrorKind> {
     match field {
         None => Ok(None),
         Some(s) => {
@@ -195,7 +437,7 @@ fn parse_numeric_field(field: Option<&str>) -> Result<Option<usize>, ReadError>
                 Ok(None)
             } else {
                 Ok(Some(s.parse::<usize>().map_err(|_| {
-                    ReadError::ParseIntField {
+                    ReadErrorKind::ParseIntField {
                         value: s.to_owned(),
                     }
                 })?))
@@ -319,12 +561,11 @@ where
 #[cfg(test)]
 mod tests {
     use std::fs::File;
-    use std::io::{BufRead, Cursor, Read};
+    use std::io::{BufRead, BufReader, Cursor, Read};
     use std::str;
 
-    use failure::Error;
-
     use super::{ReadSentence, WriteSentence, Writer};
+    use crate::error::{Error, ReadErrorKind};
     use crate::graph::Sentence;
     use crate::tests::{read_sentences, TEST_SENTENCES};
 
@@ -334,6 +575,8 @@ mod tests {
 
     static EMPTY: &str = "testdata/empty.conll";
 
+    static COMMENTS: &str = "testdata/comments.conll";
+
     fn read_file(filename: &str) -> Result<String, Error> {
         let mut f = File::open(filename)?;
         let mut contents = String::new();
@@ -347,22 +590,47 @@ mod tests {
 
     fn test_parsing(correct: &[Sentence], fragment: &str) {
         let sentences = read_sentences(fragment);
-        assert_eq!(correct.as_ref(), sentences.as_slice());
+        assert_eq!(correct, sentences.as_slice());
     }
 
     #[test]
     fn reader() {
-        test_parsing(&*TEST_SENTENCES, BASIC);
+        test_parsing(&TEST_SENTENCES, BASIC);
     }
 
     #[test]
     fn reader_robust() {
-        test_parsing(&*TEST_SENTENCES, DOUBLE_NEWLINE);
+        test_parsing(&TEST_SENTENCES, DOUBLE_NEWLINE);
     }
 
     #[test]
     fn reader_marked_empty() {
-        test_parsing(&*TEST_SENTENCES, EMPTY);
+        test_parsing(&TEST_SENTENCES, EMPTY);
+    }
+
+    #[test]
+    fn reader_comments() {
+        let sentences = read_sentences(COMMENTS);
+        assert_eq!(sentences.len(), 2);
+
+        assert_eq!(
+            sentences[0].comments(),
+            &[
+                "newdoc id = tueba",
+                "sent_id = 1",
+                "text = Die Großaufnahme"
+            ]
+        );
+        assert_eq!(sentences[0].metadata().get("sent_id"), Some(&"1"));
+        assert_eq!(sentences[1].comments(), &["sent_id = 2"]);
+        assert_eq!(sentences[1].len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "ParseIntField")]
+    fn reader_rejects_comment_after_token() {
+        let mut reader = super::Reader::new(string_reader("1\tTest\n# comment"));
+        reader.read_sentence().unwrap();
     }
 
     #[test]
@@ -372,6 +640,135 @@ mod tests {
         reader.read_sentence().unwrap();
     }
 
+    #[test]
+    fn reader_error_position() {
+        let mut reader = super::Reader::new(string_reader(
+            "# sent_id = 1\n1\tTest\n\n\n1\tTest\n2\tTest\t_\t_\t_\t_\tx\n",
+        ));
+        reader.read_sentence().unwrap();
+
+        let err = match reader.read_sentence() {
+            Err(Error::Read(err)) => err,
+            _ => panic!("expected a read error"),
+        };
+        assert_eq!(err.line(), 6);
+        assert_eq!(err.sentence(), 2);
+        assert_eq!(err.content(), "2\tTest\t_\t_\t_\t_\tx");
+        assert_eq!(
+            err.kind(),
+            &ReadErrorKind::ParseIntField {
+                value: "x".to_owned()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 6 (sentence 2): cannot parse as integer field: x"
+        );
+    }
+
+    #[test]
+    fn reader_lenient() {
+        let reader = super::Reader::new(string_reader(
+            "1\tA\n\n1\tB\n2\tB\t_\t_\t_\t_\tx\n3\tB\n\n1\tC\n\n_\tD\n",
+        ))
+        .lenient(true);
+
+        let mut sentences = reader.sentences();
+        let forms = sentences
+            .by_ref()
+            .map(|s| s.unwrap()[1].token().unwrap().form().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(forms, &["A", "C"]);
+
+        let skipped = sentences.get_ref().skipped();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!((skipped[0].line(), skipped[0].sentence()), (4, 2));
+        assert_eq!((skipped[1].line(), skipped[1].sentence()), (9, 4));
+        assert_eq!(
+            skipped[1].kind(),
+            &ReadErrorKind::ParseIdentifierField {
+                value: "_".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reader_not_lenient_by_default() {
+        let mut reader = super::Reader::new(string_reader("_\tA\n\n1\tB\n"));
+        assert!(reader.read_sentence().is_err());
+        assert!(reader.skipped().is_empty());
+    }
+
+    fn strict_error(data: &str) -> ReadErrorKind {
+        let mut reader = super::Reader::new(string_reader(data)).strict(true);
+        match reader.read_sentence() {
+            Err(Error::Read(err)) => err.kind().clone(),
+            _ => panic!("expected a read error"),
+        }
+    }
+
+    #[test]
+    fn reader_rejects_out_of_bounds_head() {
+        let mut reader = super::Reader::new(string_reader(
+            "1\tA\t_\t_\t_\t_\t0\tROOT\n2\tB\t_\t_\t_\t_\t3\tDET\n",
+        ));
+        let err = match reader.read_sentence() {
+            Err(Error::Read(err)) => err,
+            _ => panic!("expected a read error"),
+        };
+        assert_eq!(err.line(), 2);
+        assert_eq!(
+            err.kind(),
+            &ReadErrorKind::HeadOutOfBounds {
+                value: "3".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reader_strict() {
+        let reader =
+            super::Reader::new(File::open(EMPTY).map(BufReader::new).unwrap()).strict(true);
+        let sentences = reader.sentences().collect::<Result<Vec<_>, _>>();
+        assert_eq!(sentences.unwrap(), *TEST_SENTENCES);
+    }
+
+    #[test]
+    fn reader_strict_rejects_non_sequential_id() {
+        assert_eq!(
+            strict_error("1\tA\t_\t_\t_\t_\t0\tROOT\t_\t_\n3\tB\t_\t_\t_\t_\t1\tDET\t_\t_\n"),
+            ReadErrorKind::NonSequentialIdentifier {
+                value: "3 (expected 2)".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reader_strict_rejects_column_count() {
+        assert_eq!(
+            strict_error("1\tA\t_\t_\t_\t_\t0\tROOT\n"),
+            ReadErrorKind::InvalidColumnCount {
+                value: "8 (expected 10)".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reader_strict_rejects_separators() {
+        assert_eq!(
+            strict_error("1\tA\t\t_\t_\t_\t_\t0\tROOT\t_\t_\n"),
+            ReadErrorKind::InvalidSeparator {
+                value: "".to_owned()
+            }
+        );
+        assert_eq!(
+            strict_error("1\tA B\t_\t_\t_\t_\t0\tROOT\t_\t_\n"),
+            ReadErrorKind::InvalidSeparator {
+                value: "A B".to_owned()
+            }
+        );
+    }
+
     #[test]
     #[should_panic(expected = "ParseIdentifierField")]
     fn reader_rejects_underscore_id() {
@@ -385,7 +782,7 @@ mod tests {
         let mut writer = Writer::new(Box::new(output));
 
         for sentence in &*TEST_SENTENCES {
-            writer.write_sentence(&sentence).unwrap();
+            writer.write_sentence(sentence).unwrap();
         }
 
         assert_eq!(
@@ -393,4 +790,19 @@ mod tests {
             str::from_utf8(writer.get_ref()).unwrap()
         );
     }
+
+    #[test]
+    fn writer_comments() {
+        let output = Vec::new();
+        let mut writer = Writer::new(Box::new(output));
+
+        for sentence in read_sentences(COMMENTS) {
+            writer.write_sentence(&sentence).unwrap();
+        }
+
+        assert_eq!(
+            read_file(COMMENTS).unwrap(),
+            str::from_utf8(writer.get_ref()).unwrap()
+        );
+    }
 }
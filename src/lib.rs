@@ -1,5 +1,9 @@
+pub mod conllu;
+
+pub mod eval;
+
 mod error;
-pub use crate::error::{GraphError, ReadError};
+pub use crate::error::{Error, EvalError, GraphError, QueryError, ReadError, ReadErrorKind};
 
 pub mod graph;
 
@@ -10,7 +14,15 @@ pub mod io;
 
 pub mod proj;
 
+pub mod query;
+
+pub mod structure;
+
 pub mod token;
 
+pub mod transition;
+
+pub mod validate;
+
 #[cfg(test)]
 mod tests;
@@ -1,22 +1,91 @@
 //! Projectivization/deprojectivization of graphs.
 
-use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::cmp::{max, min, Reverse};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use itertools::Itertools;
 use petgraph::graph::{node_index, EdgeIndex, NodeIndex};
 use petgraph::visit::{Bfs, EdgeRef, NodeFiltered, Walker};
 use petgraph::{Directed, Direction, Graph};
 
-use crate::graph::{DepTriple, Sentence};
-use crate::{BfsWithDepth, GraphError};
+use crate::graph::{DepGraph, DepGraphMut, DepTriple, Sentence};
+use crate::token::{Features, Token};
+use crate::{BfsWithDepth, Error, GraphError};
 
 pub trait Deprojectivize {
-    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), GraphError>;
+    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), Error>;
+
+    /// Deprojectivize the projective layer of a sentence.
+    ///
+    /// The deprojectivized tree replaces the relations of the projective
+    /// layer (PHEAD/PDEPREL). The non-projective layer (HEAD/DEPREL) is
+    /// left intact. Token features that the deprojectivizer changes, such
+    /// as the original heads that are removed by `FeatureProjectivizer`,
+    /// are copied to the sentence.
+    fn deprojectivize_proj_layer(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let mut non_projective = sentence.clone();
+        copy_relations(
+            &sentence.proj_dep_graph(),
+            &mut non_projective.dep_graph_mut(),
+        );
+        self.deprojectivize(&mut non_projective)?;
+
+        copy_features(&non_projective, sentence);
+        copy_relations(
+            &non_projective.dep_graph(),
+            &mut sentence.proj_dep_graph_mut(),
+        );
+
+        Ok(())
+    }
 }
 
 pub trait Projectivize {
-    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), GraphError>;
+    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), Error>;
+
+    /// Projectivize a sentence into its projective layer.
+    ///
+    /// The projectivized tree is stored in the projective layer
+    /// (PHEAD/PDEPREL), replacing its existing relations. The
+    /// non-projective layer (HEAD/DEPREL) is left intact. Token features
+    /// that the projectivizer sets, such as the original heads that are
+    /// recorded by `FeatureProjectivizer`, are copied to the sentence, so
+    /// that the projective layer can be restored with
+    /// `Deprojectivize::deprojectivize_proj_layer`.
+    fn projectivize_to_proj_layer(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let mut projective = sentence.clone();
+        self.projectivize(&mut projective)?;
+
+        copy_features(&projective, sentence);
+        copy_relations(&projective.dep_graph(), &mut sentence.proj_dep_graph_mut());
+
+        Ok(())
+    }
+}
+
+/// Copy the features of the tokens of one sentence to another sentence
+/// with the same tokens.
+fn copy_features(from: &Sentence, to: &mut Sentence) {
+    for idx in 1..from.len() {
+        if let (Some(token), Some(from_token)) = (to[idx].token_mut(), from[idx].token()) {
+            token.set_features(from_token.features().cloned());
+        }
+    }
+}
+
+/// Replace the relations of a layer by the relations of another layer
+/// with the same tokens.
+fn copy_relations(from: &DepGraph, to: &mut DepGraphMut) {
+    for dependent in 1..from.len() {
+        match from.head(dependent) {
+            Some(triple) => {
+                to.add_deprel(DepTriple::new(triple.head(), triple.relation(), dependent))
+            }
+            None => {
+                to.remove_head_rel(dependent);
+            }
+        }
+    }
 }
 
 /// A projectivizer using the 'head' marking strategy. See: *Pseudo-Projective
@@ -51,7 +120,7 @@ impl HeadProjectivizer {
                 .expect("Endpoints of lifted edge could not be found");
 
             if let Some(new_head) =
-                self.search_attachment_point(&graph, cur_head, *lifted_node, pref_head_rel)
+                self.search_attachment_point(graph, cur_head, *lifted_node, pref_head_rel)
             {
                 let head_rel = graph
                     .remove_edge(head_edge)
@@ -187,7 +256,7 @@ impl Default for HeadProjectivizer {
 }
 
 impl Projectivize for HeadProjectivizer {
-    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), GraphError> {
+    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
         let mut graph = simplify_graph(sentence)?;
         let mut lifted = HashSet::new();
 
@@ -211,7 +280,7 @@ impl Projectivize for HeadProjectivizer {
 }
 
 impl Deprojectivize for HeadProjectivizer {
-    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), GraphError> {
+    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
         let graph = simplify_graph(sentence)?;
 
         // Find nodes and corresponding edges that are lifted and remove
@@ -225,7 +294,7 @@ impl Deprojectivize for HeadProjectivizer {
         let mut lifted_sorted = Vec::new();
         let mut bfs = Bfs::new(&graph, node_index(0));
         while let Some(node) = bfs.next(&graph) {
-            if head_labels.get(&node).is_some() {
+            if head_labels.contains_key(&node) {
                 lifted_sorted.push(node);
             }
         }
@@ -242,7 +311,474 @@ impl Deprojectivize for HeadProjectivizer {
     }
 }
 
-pub fn simplify_graph(sentence: &Sentence) -> Result<Graph<(), String, Directed>, GraphError> {
+/// A projectivizer using the 'path' marking strategy. See: *Pseudo-Projective
+/// Dependency Parsing*, Nivre and Nilsson, 2005.
+///
+/// A lifted relation is marked with `↑` (`rel↑`). Relations on the path
+/// from the linear head to the syntactic head of a lifted token are marked
+/// with `↓` (`rel↓`).
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct PathProjectivizer;
+
+impl PathProjectivizer {
+    pub fn new() -> Self {
+        PathProjectivizer {}
+    }
+}
+
+impl Default for PathProjectivizer {
+    fn default() -> Self {
+        PathProjectivizer
+    }
+}
+
+impl Projectivize for PathProjectivizer {
+    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let mut graph = simplify_graph(sentence)?;
+        let marks = lift_marked(&mut graph);
+
+        let graph = graph.map(
+            |_, &node_val| node_val,
+            |edge_idx, rel| {
+                let (_, dep) = graph.edge_endpoints(edge_idx).unwrap();
+                let lift_mark = if marks.lifted.contains_key(&dep) {
+                    LIFT_MARKER
+                } else {
+                    ""
+                };
+                format!("{}{}{}", rel, lift_mark, marks.path_marker(dep))
+            },
+        );
+
+        update_sentence(&graph, sentence);
+
+        Ok(())
+    }
+}
+
+impl Deprojectivize for PathProjectivizer {
+    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let graph = simplify_graph(sentence)?;
+
+        let mut marks = LiftMarks::default();
+        let mut graph = graph.map(
+            |_, &node_val| node_val,
+            |edge_idx, rel| {
+                let (_, dep) = graph.edge_endpoints(edge_idx).unwrap();
+                let rel = marks.strip_path_marker(dep, rel);
+                match rel.strip_suffix(LIFT_MARKER) {
+                    Some(rel) => {
+                        marks.lifted.insert(dep, None);
+                        rel.to_owned()
+                    }
+                    None => rel.to_owned(),
+                }
+            },
+        );
+
+        lower_marked(&mut graph, &marks);
+
+        update_sentence(&graph, sentence);
+
+        Ok(())
+    }
+}
+
+/// A projectivizer using the 'head+path' marking strategy. See:
+/// *Pseudo-Projective Dependency Parsing*, Nivre and Nilsson, 2005.
+///
+/// A lifted relation is marked with the relation of the syntactic head,
+/// as in the 'head' strategy (`rel|head_rel`). Relations on the path from
+/// the linear head to the syntactic head of a lifted token are marked with
+/// `↓` (`rel↓`).
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct HeadPathProjectivizer;
+
+impl HeadPathProjectivizer {
+    pub fn new() -> Self {
+        HeadPathProjectivizer {}
+    }
+}
+
+impl Default for HeadPathProjectivizer {
+    fn default() -> Self {
+        HeadPathProjectivizer
+    }
+}
+
+impl Projectivize for HeadPathProjectivizer {
+    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let mut graph = simplify_graph(sentence)?;
+        let marks = lift_marked(&mut graph);
+
+        let graph = graph.map(
+            |_, &node_val| node_val,
+            |edge_idx, rel| {
+                let (_, dep) = graph.edge_endpoints(edge_idx).unwrap();
+                match marks.lifted.get(&dep) {
+                    Some(Some(head_rel)) => {
+                        format!("{}|{}{}", rel, head_rel, marks.path_marker(dep))
+                    }
+                    _ => format!("{}{}", rel, marks.path_marker(dep)),
+                }
+            },
+        );
+
+        update_sentence(&graph, sentence);
+
+        Ok(())
+    }
+}
+
+impl Deprojectivize for HeadPathProjectivizer {
+    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        let graph = simplify_graph(sentence)?;
+
+        let mut marks = LiftMarks::default();
+        let mut graph = graph.map(
+            |_, &node_val| node_val,
+            |edge_idx, rel| {
+                let (_, dep) = graph.edge_endpoints(edge_idx).unwrap();
+                let rel = marks.strip_path_marker(dep, rel);
+                match rel.find('|') {
+                    Some(sep_idx) => {
+                        marks
+                            .lifted
+                            .insert(dep, Some(rel[sep_idx + 1..].to_owned()));
+                        rel[..sep_idx].to_owned()
+                    }
+                    None => rel.to_owned(),
+                }
+            },
+        );
+
+        lower_marked(&mut graph, &marks);
+
+        update_sentence(&graph, sentence);
+
+        Ok(())
+    }
+}
+
+/// A projectivizer that records lifted relations in token features.
+///
+/// Non-projective relations are lifted as in the 'head' strategy, but
+/// dependency relations are not changed. Instead, the original head of
+/// a lifted token is stored in a feature of the token (`orig_head` by
+/// default). Deprojectivization restores the original heads from this
+/// feature, so that the original tree is recovered exactly.
+///
+/// Projectivization fails if a token already has the feature, since
+/// deprojectivization could not tell it apart from a lifted token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeatureProjectivizer {
+    feature: String,
+}
+
+impl FeatureProjectivizer {
+    /// Construct a projectivizer that uses the `orig_head` feature.
+    pub fn new() -> Self {
+        FeatureProjectivizer {
+            feature: "orig_head".to_owned(),
+        }
+    }
+
+    /// Set the feature that stores the original head of lifted tokens.
+    pub fn feature(mut self, feature: impl Into<String>) -> Self {
+        self.feature = feature.into();
+        self
+    }
+}
+
+impl Default for FeatureProjectivizer {
+    fn default() -> Self {
+        FeatureProjectivizer::new()
+    }
+}
+
+impl Projectivize for FeatureProjectivizer {
+    fn projectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        if let Some(dep) = (1..sentence.len()).find(|&dep| {
+            sentence[dep]
+                .token()
+                .and_then(Token::features)
+                .map(|features| features.contains_key(&self.feature))
+                .unwrap_or(false)
+        }) {
+            return Err(GraphError::HeadFeatureExists {
+                value: format!("{} (token {})", self.feature, dep),
+            }
+            .into());
+        }
+
+        let mut graph = simplify_graph(sentence)?;
+        let marks = lift_marked(&mut graph);
+
+        let orig_heads = marks
+            .lifted
+            .keys()
+            .map(|dep| {
+                let head = sentence
+                    .dep_graph()
+                    .head(dep.index())
+                    .expect("Lifted token without a head")
+                    .head();
+                (dep.index(), head)
+            })
+            .collect::<Vec<_>>();
+
+        update_sentence(&graph, sentence);
+
+        for (dep, head) in orig_heads {
+            let token = sentence[dep]
+                .token_mut()
+                .expect("Lifted node is not a token");
+            if token.features().is_none() {
+                token.set_features(Some(Features::new()));
+            }
+
+            token
+                .features_mut()
+                .unwrap()
+                .insert(self.feature.clone(), Some(head.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+impl Deprojectivize for FeatureProjectivizer {
+    fn deprojectivize(&self, sentence: &mut Sentence) -> Result<(), Error> {
+        // Collect the original heads first, so that the sentence is not
+        // modified when a head feature is invalid.
+        let mut orig_heads = Vec::new();
+        for dep in 1..sentence.len() {
+            let value = match sentence[dep]
+                .token()
+                .and_then(Token::features)
+                .and_then(|features| features.get(&self.feature))
+            {
+                Some(value) => value.as_deref(),
+                None => continue,
+            };
+
+            let head = value
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|&head| head < sentence.len() && head != dep)
+                .ok_or_else(|| GraphError::InvalidHeadFeature {
+                    value: format!(
+                        "{}={} (token {})",
+                        self.feature,
+                        value.unwrap_or_default(),
+                        dep
+                    ),
+                })?;
+
+            orig_heads.push((dep, head));
+        }
+
+        for (dep, head) in orig_heads {
+            let relation = sentence
+                .dep_graph()
+                .head(dep)
+                .and_then(|triple| triple.relation().map(ToOwned::to_owned));
+            sentence
+                .dep_graph_mut()
+                .add_deprel(DepTriple::new(head, relation, dep));
+
+            let token = sentence[dep].token_mut().unwrap();
+            let features = token.features_mut().unwrap();
+            features.remove(&self.feature);
+            if features.is_empty() {
+                token.set_features(None);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Marker of lifted relations in the 'path' strategy.
+static LIFT_MARKER: &str = "\u{2191}";
+
+/// Marker of relations on a lifting path.
+static PATH_MARKER: &str = "\u{2193}";
+
+/// Lifted tokens and lifting paths, indexed by dependent.
+#[derive(Default)]
+struct LiftMarks {
+    /// Lifted tokens, with the relation of their syntactic head when it
+    /// is known.
+    lifted: HashMap<NodeIndex, Option<String>>,
+
+    /// Tokens that are attached by a relation on a lifting path.
+    path: HashSet<NodeIndex>,
+}
+
+impl LiftMarks {
+    fn path_marker(&self, dep: NodeIndex) -> &'static str {
+        if self.path.contains(&dep) {
+            PATH_MARKER
+        } else {
+            ""
+        }
+    }
+
+    /// Strip the path marker from the relation of `dep`, recording
+    /// whether the relation is on a lifting path.
+    fn strip_path_marker<'a>(&mut self, dep: NodeIndex, rel: &'a str) -> &'a str {
+        match rel.strip_suffix(PATH_MARKER) {
+            Some(rel) => {
+                self.path.insert(dep);
+                rel
+            }
+            None => rel,
+        }
+    }
+}
+
+/// Lift non-projective edges until the graph is projective.
+///
+/// The relations in the graph are not modified. Instead, the lifted tokens
+/// and lifting paths are returned.
+fn lift_marked(graph: &mut Graph<(), String, Directed>) -> LiftMarks {
+    let mut marks = LiftMarks::default();
+
+    loop {
+        let np_edges = non_projective_edges(graph);
+        if np_edges.is_empty() {
+            break;
+        }
+
+        let edge_idx = np_edges[0];
+        let (head, dep) = graph
+            .edge_endpoints(edge_idx)
+            .expect("Cannot find endpoints of to-be lifted edge");
+        let parent_edge = graph
+            .first_edge(head, Direction::Incoming)
+            .expect("Cannot find incoming edge of the to-be lifted node");
+        let (parent, _) = graph
+            .edge_endpoints(parent_edge)
+            .expect("Cannot find endpoints of parent edge");
+        let head_rel = graph[parent_edge].clone();
+
+        let rel = graph
+            .remove_edge(edge_idx)
+            .expect("Cannot remove edge to-be lifted");
+        graph.add_edge(parent, dep, rel);
+
+        marks.lifted.entry(dep).or_insert(Some(head_rel));
+        marks.path.insert(head);
+    }
+
+    marks
+}
+
+/// Reattach lifted tokens to their syntactic heads.
+///
+/// Lifted tokens are processed by increasing depth. The syntactic head of
+/// a lifted token is searched among the tokens that are reachable from its
+/// current head through relations on a lifting path. Since only tokens
+/// with a non-projective relation are lifted, tokens that would result in
+/// a non-projective relation are preferred. Then, if the relation of the
+/// syntactic head is known, the least deep token with that relation is
+/// used. Otherwise, the deepest token is used. Remaining ties are broken
+/// by picking the token that is closest to the lifted token.
+fn lower_marked(graph: &mut Graph<(), String, Directed>, marks: &LiftMarks) {
+    let mut lifted_sorted = Vec::new();
+    let mut bfs = Bfs::new(&*graph, node_index(0));
+    while let Some(node) = bfs.next(&*graph) {
+        if marks.lifted.contains_key(&node) {
+            lifted_sorted.push(node);
+        }
+    }
+
+    for lifted_node in lifted_sorted {
+        let head_edge = graph
+            .first_edge(lifted_node, Direction::Incoming)
+            .expect("Lifted node without an incoming edge");
+        let (cur_head, _) = graph
+            .edge_endpoints(head_edge)
+            .expect("Endpoints of lifted edge could not be found");
+
+        // Find the tokens on lifting paths from the current head. The
+        // lifted token and its descendants are excluded to avoid cycles.
+        let mut candidates = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((cur_head, 0));
+        while let Some((node, depth)) = queue.pop_front() {
+            for edge in graph.edges(node) {
+                let dep = edge.target();
+                if dep != lifted_node && marks.path.contains(&dep) {
+                    candidates.push((dep, depth + 1));
+                    queue.push_back((dep, depth + 1));
+                }
+            }
+        }
+
+        let distance = |node: NodeIndex| {
+            max(node.index(), lifted_node.index()) - min(node.index(), lifted_node.index())
+        };
+
+        let new_head = match marks.lifted[&lifted_node] {
+            Some(ref head_rel) => candidates
+                .into_iter()
+                .filter(|&(node, _)| {
+                    graph
+                        .first_edge(node, Direction::Incoming)
+                        .map(|edge| &graph[edge] == head_rel)
+                        .unwrap_or(false)
+                })
+                .min_by_key(|&(node, depth)| {
+                    (
+                        is_projective_edge(graph, node, lifted_node),
+                        depth,
+                        distance(node),
+                    )
+                }),
+            None => candidates.into_iter().min_by_key(|&(node, depth)| {
+                (
+                    is_projective_edge(graph, node, lifted_node),
+                    Reverse(depth),
+                    distance(node),
+                )
+            }),
+        };
+
+        if let Some((new_head, _)) = new_head {
+            let rel = graph
+                .remove_edge(head_edge)
+                .expect("Lifted edge to be removed could not be found");
+            graph.add_edge(new_head, lifted_node, rel);
+        }
+    }
+}
+
+/// Check whether an edge from `head` to `dep` would be projective.
+///
+/// An edge is projective when `head` dominates all tokens between `head`
+/// and `dep`.
+fn is_projective_edge(
+    graph: &Graph<(), String, Directed>,
+    head: NodeIndex,
+    dep: NodeIndex,
+) -> bool {
+    (min(head.index(), dep.index()) + 1..max(head.index(), dep.index())).all(|idx| {
+        let mut node = node_index(idx);
+        loop {
+            if node == head {
+                return true;
+            }
+
+            node = match graph.neighbors_directed(node, Direction::Incoming).next() {
+                Some(parent) if parent != node_index(idx) => parent,
+                _ => return false,
+            };
+        }
+    })
+}
+
+pub fn simplify_graph(sentence: &Sentence) -> Result<Graph<(), String, Directed>, Error> {
     let mut edges = Vec::with_capacity(sentence.len() + 1);
     for idx in 0..sentence.len() {
         let triple = match sentence.dep_graph().head(idx) {
@@ -259,7 +795,8 @@ pub fn simplify_graph(sentence: &Sentence) -> Result<Graph<(), String, Directed>
                         triple.head(),
                         triple.dependent()
                     ),
-                })
+                }
+                .into())
             }
         };
 
@@ -274,25 +811,58 @@ pub fn simplify_graph(sentence: &Sentence) -> Result<Graph<(), String, Directed>
 }
 
 /// Returns non-projective edges in the graph, ordered by length.
+///
+/// An edge is non-projective when its head does not dominate all the
+/// nodes between its head and dependent. If the graph is a forest, this
+/// is checked in *O(log n)* time per edge, see `non_projective_deprels`.
+/// Otherwise, the nodes that are dominated by a head are found by
+/// traversing the graph.
 pub fn non_projective_edges(graph: &Graph<(), String, Directed>) -> Vec<EdgeIndex> {
+    let mut dependents = vec![Vec::new(); graph.node_count()];
+    let mut in_degree = vec![0; graph.node_count()];
+    for edge in graph.edge_references() {
+        dependents[edge.source().index()].push(edge.target().index());
+        in_degree[edge.target().index()] += 1;
+    }
+
+    // Nodes with multiple heads break the pre-order numbering.
+    let pre_order = if in_degree.iter().all(|&degree| degree <= 1) {
+        Some(PreOrder::new(
+            &dependents,
+            (0..graph.node_count()).filter(|&node| in_degree[node] == 0),
+        ))
+    } else {
+        None
+    };
+
     let mut non_projective = Vec::new();
 
     for i in 0..graph.node_count() {
-        let mut i_reachable = HashSet::new();
-        let mut bfs = Bfs::new(&graph, node_index(i));
-        while let Some(node) = bfs.next(&graph) {
-            i_reachable.insert(node.index());
-        }
+        let mut i_reachable = None;
 
         for edge in graph.edges(node_index(i)) {
-            // An edge i -> k is projective, iff:
-            //
-            // i > j > k or i < j < k, and i ->* j
-            for j in min(i, edge.target().index())..max(i, edge.target().index()) {
-                if !i_reachable.contains(&j) {
-                    non_projective.push(edge);
-                    break;
+            let target = edge.target().index();
+            let projective = match pre_order
+                .as_ref()
+                .and_then(|pre_order| pre_order.is_projective(i, target))
+            {
+                Some(projective) => projective,
+                None => {
+                    // An edge i -> k is projective, iff:
+                    //
+                    // i > j > k or i < j < k, and i ->* j
+                    let i_reachable = i_reachable.get_or_insert_with(|| {
+                        Bfs::new(&graph, node_index(i))
+                            .iter(&graph)
+                            .map(NodeIndex::index)
+                            .collect::<HashSet<_>>()
+                    });
+                    (min(i, target)..max(i, target)).all(|j| i_reachable.contains(&j))
                 }
+            };
+
+            if !projective {
+                non_projective.push(edge);
             }
         }
     }
@@ -309,6 +879,138 @@ pub fn non_projective_edges(graph: &Graph<(), String, Directed>) -> Vec<EdgeInde
     non_projective.iter().map(EdgeRef::id).collect()
 }
 
+/// Returns the non-projective relations of a dependency graph.
+///
+/// A relation is non-projective when its head does not dominate all the
+/// tokens between its head and dependent. The relations are returned in
+/// the order of their dependents. Tokens that are not connected to the
+/// root are not dominated by any token; their relations are not
+/// reported.
+///
+/// This function runs in *O(n log n)* time: tokens are numbered in
+/// depth-first pre-order, such that the descendants of a token form a
+/// contiguous range of numbers. A relation is then projective iff the
+/// minimum and maximum numbers of the tokens between its head and
+/// dependent are in the range of the head.
+pub fn non_projective_deprels<'a>(graph: &'a DepGraph<'_>) -> Vec<DepTriple<&'a str>> {
+    let mut dependents = vec![Vec::new(); graph.len()];
+    for dependent in 1..graph.len() {
+        if let Some(triple) = graph.head(dependent) {
+            dependents[triple.head()].push(dependent);
+        }
+    }
+
+    let pre_order = PreOrder::new(&dependents, Some(0));
+
+    (1..graph.len())
+        .filter_map(|dependent| graph.head(dependent))
+        .filter(|triple| pre_order.is_projective(triple.head(), triple.dependent()) == Some(false))
+        .collect()
+}
+
+/// Depth-first pre-order numbering of a forest.
+struct PreOrder {
+    /// The number of each node, `usize::MAX` for nodes that are not
+    /// reachable from a root.
+    numbers: Vec<usize>,
+
+    /// The number of nodes in the subtree of each node.
+    subtree_len: Vec<usize>,
+
+    ranges: RangeMinMax,
+}
+
+impl PreOrder {
+    /// Number the trees of a forest, given the dependents of each node
+    /// and the roots of the trees.
+    fn new(dependents: &[Vec<usize>], roots: impl IntoIterator<Item = usize>) -> Self {
+        let mut numbers = vec![usize::MAX; dependents.len()];
+        let mut subtree_len = vec![0; dependents.len()];
+        let mut order = Vec::with_capacity(dependents.len());
+        for root in roots {
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                numbers[node] = order.len();
+                order.push(node);
+                stack.extend(dependents[node].iter().rev());
+            }
+        }
+
+        for &node in order.iter().rev() {
+            subtree_len[node] = 1 + dependents[node]
+                .iter()
+                .map(|&dependent| subtree_len[dependent])
+                .sum::<usize>();
+        }
+
+        let ranges = RangeMinMax::new(&numbers);
+
+        PreOrder {
+            numbers,
+            subtree_len,
+            ranges,
+        }
+    }
+
+    /// Check whether the relation between `head` and `dependent` is
+    /// projective.
+    ///
+    /// Returns `None` if the relation is not empty and `head` is not
+    /// reachable from a root.
+    fn is_projective(&self, head: usize, dependent: usize) -> Option<bool> {
+        let (first, last) = (min(head, dependent) + 1, max(head, dependent));
+        if first >= last {
+            return Some(true);
+        }
+
+        let number = self.numbers[head];
+        if number == usize::MAX {
+            return None;
+        }
+
+        let (min_number, max_number) = self.ranges.query(first, last);
+        Some(min_number >= number && max_number < number + self.subtree_len[head])
+    }
+}
+
+/// Sparse table for range minimum/maximum queries.
+struct RangeMinMax {
+    /// Minima/maxima of ranges of length `2^level`, indexed by level.
+    levels: Vec<Vec<(usize, usize)>>,
+}
+
+impl RangeMinMax {
+    fn new(values: &[usize]) -> Self {
+        let mut levels = vec![values.iter().map(|&v| (v, v)).collect::<Vec<_>>()];
+
+        let mut len = 1;
+        while len * 2 <= values.len() {
+            let prev = levels.last().unwrap();
+            let level = (0..=values.len() - len * 2)
+                .map(|idx| {
+                    let (min_a, max_a) = prev[idx];
+                    let (min_b, max_b) = prev[idx + len];
+                    (min(min_a, min_b), max(max_a, max_b))
+                })
+                .collect();
+            levels.push(level);
+            len *= 2;
+        }
+
+        RangeMinMax { levels }
+    }
+
+    /// Get the minimum and maximum of the values in `start..end`.
+    ///
+    /// The range must not be empty.
+    fn query(&self, start: usize, end: usize) -> (usize, usize) {
+        let level = (usize::BITS - 1 - (end - start).leading_zeros()) as usize;
+        let (min_a, max_a) = self.levels[level][start];
+        let (min_b, max_b) = self.levels[level][end - (1 << level)];
+        (min(min_a, min_b), max(max_a, max_b))
+    }
+}
+
 /// Update a sentence with dependency relations from a graph.
 fn update_sentence(graph: &Graph<(), String, Directed>, sentence: &mut Sentence) {
     let mut sent_graph = sentence.dep_graph_mut();
@@ -323,14 +1025,25 @@ fn update_sentence(graph: &Graph<(), String, Directed>, sentence: &mut Sentence)
 
 #[cfg(test)]
 mod tests {
+    use std::cmp::{max, min};
+    use std::collections::HashSet;
+
     use lazy_static::lazy_static;
     use petgraph::graph::{node_index, NodeIndex};
+    use petgraph::visit::{Bfs, EdgeRef, Walker};
+    use petgraph::{Directed, Graph};
+    use quickcheck::quickcheck;
 
+    use crate::error::{Error, GraphError};
+    use crate::graph::DepTriple;
     use crate::graph::Sentence;
     use crate::proj::{
-        non_projective_edges, simplify_graph, Deprojectivize, HeadProjectivizer, Projectivize,
+        non_projective_deprels, non_projective_edges, simplify_graph, Deprojectivize,
+        FeatureProjectivizer, HeadPathProjectivizer, HeadProjectivizer, PathProjectivizer,
+        Projectivize,
     };
     use crate::tests::read_sentences;
+    use crate::token::{Features, Token};
 
     lazy_static! {
         static ref NON_PROJECTIVE_EDGES: Vec<Vec<(NodeIndex, NodeIndex)>> = vec![
@@ -359,6 +1072,29 @@ mod tests {
         np_edges
     }
 
+    /// Construct a random tree from a seed.
+    ///
+    /// The tokens are added to the tree in an order that is determined by
+    /// the seed, attaching each token to a token that was added earlier.
+    fn random_tree(seed: &[usize]) -> Sentence {
+        let mut order = (1..=seed.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&token| (seed[token - 1], token));
+
+        let mut sentence = Sentence::new();
+        for _ in seed {
+            sentence.push(Token::new("x"));
+        }
+
+        for (idx, &token) in order.iter().enumerate() {
+            let head = if idx == 0 { 0 } else { order[seed[idx] % idx] };
+            sentence
+                .dep_graph_mut()
+                .add_deprel(DepTriple::new(head, Some("dep"), token));
+        }
+
+        sentence
+    }
+
     static PROJECTIVE_SENTENCES_FILENAME: &str = "testdata/projective.conll";
 
     static NONPROJECTIVE_SENTENCES_FILENAME: &str = "testdata/nonprojective.conll";
@@ -389,6 +1125,90 @@ mod tests {
         assert_eq!(*NON_PROJECTIVE_EDGES, test_edges);
     }
 
+    #[test]
+    fn non_projective_deprels_test() {
+        let test_edges: Vec<Vec<_>> = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME)
+            .iter()
+            .map(|sentence| {
+                non_projective_deprels(&sentence.dep_graph())
+                    .into_iter()
+                    .map(|triple| (node_index(triple.head()), node_index(triple.dependent())))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(*NON_PROJECTIVE_EDGES, test_edges);
+
+        for sentence in read_sentences(PROJECTIVE_SENTENCES_FILENAME) {
+            assert!(non_projective_deprels(&sentence.dep_graph()).is_empty());
+        }
+    }
+
+    /// Non-projective edges, ordered by dependent, following the
+    /// definition: an edge is non-projective when its head does not
+    /// dominate all the nodes between its head and dependent.
+    fn non_projective_reference(
+        graph: &Graph<(), String, Directed>,
+    ) -> Vec<(NodeIndex, NodeIndex)> {
+        let mut edges = graph
+            .edge_references()
+            .filter(|edge| {
+                let (head, dependent) = (edge.source().index(), edge.target().index());
+                let reachable = Bfs::new(&graph, edge.source())
+                    .iter(&graph)
+                    .map(NodeIndex::index)
+                    .collect::<HashSet<_>>();
+                (min(head, dependent)..max(head, dependent)).any(|j| !reachable.contains(&j))
+            })
+            .map(|edge| (edge.source(), edge.target()))
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|&(_, dependent)| dependent);
+        edges
+    }
+
+    #[test]
+    fn non_projective_edges_multiple_heads_test() {
+        // Not a forest: 3 has the heads 1 and 4.
+        let mut graph = Graph::new();
+        let nodes = (0..5).map(|_| graph.add_node(())).collect::<Vec<_>>();
+        for &(head, dependent) in &[(0, 2), (2, 1), (1, 3), (2, 4), (4, 3)] {
+            graph.add_edge(nodes[head], nodes[dependent], String::new());
+        }
+
+        let edges = non_projective_edges(&graph)
+            .iter()
+            .map(|idx| graph.edge_endpoints(*idx).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(edges, vec![(nodes[1], nodes[3])]);
+        assert_eq!(edges, non_projective_reference(&graph));
+    }
+
+    quickcheck! {
+        fn non_projective_edges_prop(seed: Vec<usize>) -> bool {
+            let sentence = random_tree(&seed);
+            let graph = simplify_graph(&sentence).unwrap();
+
+            let mut edges = non_projective_edges(&graph)
+                .iter()
+                .map(|idx| graph.edge_endpoints(*idx).unwrap())
+                .collect::<Vec<_>>();
+            edges.sort_by_key(|&(_, dependent)| dependent);
+
+            edges == non_projective_reference(&graph)
+        }
+
+        fn non_projective_deprels_prop(seed: Vec<usize>) -> bool {
+            let sentence = random_tree(&seed);
+            let graph = simplify_graph(&sentence).unwrap();
+
+            let deprels = non_projective_deprels(&sentence.dep_graph())
+                .into_iter()
+                .map(|triple| (node_index(triple.head()), node_index(triple.dependent())))
+                .collect::<Vec<_>>();
+
+            deprels == non_projective_reference(&graph)
+        }
+    }
+
     #[test]
     fn projectivize_test() {
         let projectivizer = HeadProjectivizer::new();
@@ -404,4 +1224,234 @@ mod tests {
 
         assert_eq!(read_sentences(PROJECTIVE_SENTENCES_FILENAME), projective);
     }
+
+    fn round_trip<P>(projectivizer: P) -> Vec<Sentence>
+    where
+        P: Projectivize + Deprojectivize,
+    {
+        let non_projective = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME);
+
+        let projective: Vec<_> = non_projective
+            .iter()
+            .cloned()
+            .map(|mut s| {
+                projectivizer
+                    .projectivize(&mut s)
+                    .expect("Cannot projectivize sentence");
+                s
+            })
+            .collect();
+
+        for sentence in &projective {
+            assert!(non_projective_edges(&simplify_graph(sentence).unwrap()).is_empty());
+        }
+
+        let deprojectivized: Vec<_> = projective
+            .iter()
+            .cloned()
+            .map(|mut s| {
+                projectivizer
+                    .deprojectivize(&mut s)
+                    .expect("Cannot deprojectivize sentence");
+                s
+            })
+            .collect();
+
+        assert_eq!(non_projective, deprojectivized);
+
+        projective
+    }
+
+    #[test]
+    fn path_round_trip_test() {
+        let projective = round_trip(PathProjectivizer::new());
+
+        // Token 1 of the first sentence is lifted from 8 to 4, the
+        // relations of 8 and 9 are on the lifting path.
+        let graph = projective[0].dep_graph();
+        assert_eq!(
+            graph.head(1),
+            Some(DepTriple::new(4, Some("PP\u{2191}"), 1))
+        );
+        assert_eq!(
+            graph.head(8),
+            Some(DepTriple::new(9, Some("OBJA\u{2193}"), 8))
+        );
+        assert_eq!(
+            graph.head(9),
+            Some(DepTriple::new(4, Some("AUX\u{2193}"), 9))
+        );
+    }
+
+    #[test]
+    fn head_path_round_trip_test() {
+        let projective = round_trip(HeadPathProjectivizer::new());
+
+        let graph = projective[0].dep_graph();
+        assert_eq!(graph.head(1), Some(DepTriple::new(4, Some("PP|OBJA"), 1)));
+        assert_eq!(
+            graph.head(8),
+            Some(DepTriple::new(9, Some("OBJA\u{2193}"), 8))
+        );
+    }
+
+    #[test]
+    fn head_path_ambiguous_heads_test() {
+        // Tokens 1 and 5 are both lifted to 3 and both have a syntactic
+        // head that is attached with 'obj'.
+        let mut sentence = Sentence::new();
+        for form in &["a", "b", "c", "d", "e"] {
+            sentence.push(Token::new(*form));
+        }
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(0, Some("root"), 3));
+        graph.add_deprel(DepTriple::new(3, Some("obj"), 2));
+        graph.add_deprel(DepTriple::new(3, Some("obj"), 4));
+        graph.add_deprel(DepTriple::new(4, Some("dep"), 1));
+        graph.add_deprel(DepTriple::new(2, Some("dep"), 5));
+
+        let original = sentence.clone();
+        let projectivizer = HeadPathProjectivizer::new();
+        projectivizer.projectivize(&mut sentence).unwrap();
+        assert!(non_projective_edges(&simplify_graph(&sentence).unwrap()).is_empty());
+        projectivizer.deprojectivize(&mut sentence).unwrap();
+        assert_eq!(original, sentence);
+    }
+
+    #[test]
+    fn feature_round_trip_test() {
+        let projective = round_trip(FeatureProjectivizer::new());
+
+        // Relations are not changed, the original head is stored in
+        // the features of the lifted token.
+        let graph = projective[0].dep_graph();
+        assert_eq!(graph.head(1), Some(DepTriple::new(4, Some("PP"), 1)));
+        assert_eq!(
+            graph[1].token().unwrap().features(),
+            Some(&Features::from("orig_head:8"))
+        );
+        assert_eq!(graph[8].token().unwrap().features(), None);
+    }
+
+    #[test]
+    fn feature_preserves_features_test() {
+        let mut sentence = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME).remove(0);
+        sentence[1]
+            .token_mut()
+            .unwrap()
+            .set_features(Some(Features::from("case:dat")));
+        let original = sentence.clone();
+
+        let projectivizer = FeatureProjectivizer::new().feature("head");
+        projectivizer.projectivize(&mut sentence).unwrap();
+        assert_eq!(
+            sentence[1].token().unwrap().features(),
+            Some(&Features::from("case:dat|head:8"))
+        );
+
+        projectivizer.deprojectivize(&mut sentence).unwrap();
+        assert_eq!(original, sentence);
+    }
+
+    #[test]
+    fn feature_invalid_head_test() {
+        let mut sentence = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME).remove(0);
+        sentence[1]
+            .token_mut()
+            .unwrap()
+            .set_features(Some(Features::from("orig_head:42")));
+        let original = sentence.clone();
+
+        assert!(FeatureProjectivizer::new()
+            .deprojectivize(&mut sentence)
+            .is_err());
+        assert_eq!(original, sentence);
+    }
+
+    #[test]
+    fn feature_head_feature_exists_test() {
+        let mut sentence = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME).remove(0);
+        let projectivizer = FeatureProjectivizer::new();
+        projectivizer.projectivize(&mut sentence).unwrap();
+        let projective = sentence.clone();
+
+        // The sentence already records the original heads.
+        match projectivizer.projectivize(&mut sentence) {
+            Err(Error::Graph(GraphError::HeadFeatureExists { value })) => {
+                assert_eq!(value, "orig_head (token 1)")
+            }
+            _ => panic!("expected a head feature error"),
+        }
+        assert_eq!(projective, sentence);
+    }
+
+    #[test]
+    fn projectivize_to_proj_layer_test() {
+        let projectivizer = HeadProjectivizer::new();
+        let non_projective = read_sentences(NONPROJECTIVE_SENTENCES_FILENAME);
+        let projective = read_sentences(PROJECTIVE_SENTENCES_FILENAME);
+
+        for (original, projective) in non_projective.iter().zip(&projective) {
+            let mut sentence = original.clone();
+            projectivizer
+                .projectivize_to_proj_layer(&mut sentence)
+                .unwrap();
+
+            for token in 0..sentence.len() {
+                assert_eq!(
+                    sentence.dep_graph().head(token),
+                    original.dep_graph().head(token)
+                );
+                assert_eq!(
+                    sentence.proj_dep_graph().head(token),
+                    projective.dep_graph().head(token)
+                );
+            }
+
+            projectivizer
+                .deprojectivize_proj_layer(&mut sentence)
+                .unwrap();
+            for token in 0..sentence.len() {
+                assert_eq!(
+                    sentence.proj_dep_graph().head(token),
+                    original.dep_graph().head(token)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn feature_projectivize_to_proj_layer_test() {
+        let projectivizer = FeatureProjectivizer::new();
+
+        for original in read_sentences(NONPROJECTIVE_SENTENCES_FILENAME) {
+            let mut sentence = original.clone();
+            projectivizer
+                .projectivize_to_proj_layer(&mut sentence)
+                .unwrap();
+
+            // Lifted tokens record their original heads.
+            assert!((1..sentence.len()).any(|token| sentence[token]
+                .token()
+                .and_then(Token::features)
+                .map(|features| features.contains_key("orig_head"))
+                .unwrap_or(false)));
+
+            projectivizer
+                .deprojectivize_proj_layer(&mut sentence)
+                .unwrap();
+
+            for token in 0..sentence.len() {
+                assert_eq!(
+                    sentence.dep_graph().head(token),
+                    original.dep_graph().head(token)
+                );
+                assert_eq!(
+                    sentence.proj_dep_graph().head(token),
+                    original.dep_graph().head(token)
+                );
+                assert_eq!(sentence[token], original[token]);
+            }
+        }
+    }
 }
@@ -0,0 +1,736 @@
+//! Tree pattern queries.
+//!
+//! This module provides a small query language to search sentences for
+//! constructions. The syntax is modeled after TIGERSearch. A query is a
+//! conjunction of clauses, separated by `&`. Each clause constrains one
+//! or two variables. Variables start with `#` and are bound to tokens.
+//!
+//! Token constraints follow a variable in square brackets:
+//!
+//! ```text
+//! #v:[pos="VVFIN" & lemma!="sein"]
+//! ```
+//!
+//! The fields `form`, `lemma`, `cpos` and `pos` refer to the token
+//! fields with the same names. The field `feat.name` refers to the value
+//! of the feature `name` and `misc.name` to the value of the
+//! miscellaneous feature `name`. Feature names that are not identifiers
+//! can be quoted, e.g. `feat."Number[psor]"`. The operator `=` requires
+//! that a field has the given value, `!=` that a field is absent or has
+//! another value.
+//!
+//! Relations between two variables are written as infix operators:
+//!
+//! * `#h > #d`: `#d` is a dependent of `#h`.
+//! * `#h >OBJA #d`: `#d` is a dependent of `#h` with the relation
+//!   `OBJA`. Relations that contain whitespace or special characters
+//!   can be quoted, e.g. `>"obl:tmod"`.
+//! * `#h >* #d`: `#d` is a descendant of `#h`.
+//! * `#a . #b`: `#a` immediately precedes `#b`.
+//! * `#a .* #b`: `#a` precedes `#b`.
+//!
+//! A variable can be constrained in any clause in which it occurs. If a
+//! variable is constrained more than once, all constraints must be
+//! satisfied. Relations are looked up in the non-projective dependency
+//! graph (`Sentence::dep_graph`).
+//!
+//! A query is compiled into a `Pattern`, which finds all bindings of the
+//! variables in a sentence. Different variables are always bound to
+//! different tokens.
+//!
+//! ```
+//! use conllx::graph::{DepTriple, Sentence};
+//! use conllx::query::Pattern;
+//! use conllx::token::TokenBuilder;
+//!
+//! let mut sentence = Sentence::new();
+//! sentence.push(TokenBuilder::new("Den").pos("ART").into());
+//! sentence.push(TokenBuilder::new("Nachweis").pos("NN").into());
+//! sentence.push(TokenBuilder::new("hat").pos("VAFIN").into());
+//! sentence.push(TokenBuilder::new("er").pos("PPER").into());
+//! sentence.push(TokenBuilder::new("geliefert").pos("VVPP").into());
+//!
+//! let mut graph = sentence.dep_graph_mut();
+//! graph.add_deprel(DepTriple::new(2, Some("DET"), 1));
+//! graph.add_deprel(DepTriple::new(5, Some("OBJA"), 2));
+//! graph.add_deprel(DepTriple::new(0, Some("ROOT"), 3));
+//! graph.add_deprel(DepTriple::new(3, Some("SUBJ"), 4));
+//! graph.add_deprel(DepTriple::new(3, Some("AUX"), 5));
+//!
+//! let pattern: Pattern = r#"#v:[pos="VVPP"] >OBJA #o & #o .* #v"#.parse().unwrap();
+//! let matches = pattern.matches(&sentence);
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].get("v"), Some(5));
+//! assert_eq!(matches[0].get("o"), Some(2));
+//! ```
+
+use std::str::FromStr;
+
+use crate::error::{Error, QueryError};
+use crate::graph::{DepGraph, Sentence};
+use crate::token::Token;
+
+/// Token field that is used in a constraint.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Field {
+    Form,
+    Lemma,
+    CPos,
+    Pos,
+    Feature(String),
+    Misc(String),
+}
+
+impl Field {
+    /// Get the value of the field in `token`.
+    ///
+    /// Returns `None` if the field is absent. Features without a value
+    /// are treated as absent.
+    fn value<'a>(&self, token: &'a Token) -> Option<&'a str> {
+        match self {
+            Field::Form => Some(token.form()),
+            Field::Lemma => token.lemma(),
+            Field::CPos => token.cpos(),
+            Field::Pos => token.pos(),
+            Field::Feature(name) => token
+                .features()
+                .and_then(|features| features.get(name))
+                .and_then(|value| value.as_deref()),
+            Field::Misc(name) => token
+                .misc()
+                .and_then(|misc| misc.get(name))
+                .and_then(|value| value.as_deref()),
+        }
+    }
+}
+
+/// Constraint on a token field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Constraint {
+    field: Field,
+    negated: bool,
+    value: String,
+}
+
+impl Constraint {
+    fn matches(&self, token: &Token) -> bool {
+        (self.field.value(token) == Some(self.value.as_str())) != self.negated
+    }
+}
+
+/// Relation between two variables.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Relation {
+    /// The second token is a dependent of the first token, optionally
+    /// with the given relation.
+    Dependent(Option<String>),
+
+    /// The second token is a descendant of the first token.
+    Descendant,
+
+    /// The first token immediately precedes the second token.
+    ImmediatelyPrecedes,
+
+    /// The first token precedes the second token.
+    Precedes,
+}
+
+impl Relation {
+    fn holds(&self, graph: &DepGraph, first: usize, second: usize) -> bool {
+        match self {
+            Relation::Dependent(relation) => match graph.head(second) {
+                Some(triple) => {
+                    triple.head() == first
+                        && relation
+                            .as_ref()
+                            .map(|relation| triple.relation() == Some(relation.as_str()))
+                            .unwrap_or(true)
+                }
+                None => false,
+            },
+            Relation::Descendant => {
+                // Bound the number of steps, in case the graph is cyclic.
+                let mut token = second;
+                for _ in 0..graph.len() {
+                    match graph.head(token) {
+                        Some(triple) if triple.head() == first => return true,
+                        Some(triple) => token = triple.head(),
+                        None => return false,
+                    }
+                }
+                false
+            }
+            Relation::ImmediatelyPrecedes => first + 1 == second,
+            Relation::Precedes => first < second,
+        }
+    }
+}
+
+/// Compiled tree pattern.
+///
+/// A pattern is constructed by parsing a query, see the module-level
+/// documentation for the query syntax.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern {
+    variables: Vec<String>,
+    constraints: Vec<Vec<Constraint>>,
+    relations: Vec<(usize, Relation, usize)>,
+}
+
+impl Pattern {
+    /// Find all bindings of the pattern variables in `sentence`.
+    ///
+    /// The matches are ordered by the tokens that are bound to the
+    /// variables, in the order in which the variables occur in the
+    /// query.
+    pub fn matches(&self, sentence: &Sentence) -> Vec<Match<'_>> {
+        let candidates = self
+            .constraints
+            .iter()
+            .map(|constraints| {
+                (1..sentence.len())
+                    .filter(|&idx| {
+                        let token = sentence[idx].token().expect("Node is not a token");
+                        constraints
+                            .iter()
+                            .all(|constraint| constraint.matches(token))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Check each relation as soon as both of its variables are bound.
+        let mut relations = vec![Vec::new(); self.variables.len()];
+        for (first, relation, second) in &self.relations {
+            relations[*first.max(second)].push((*first, relation, *second));
+        }
+
+        let mut matches = Vec::new();
+        self.search(
+            &sentence.dep_graph(),
+            &candidates,
+            &relations,
+            &mut Vec::with_capacity(self.variables.len()),
+            &mut matches,
+        );
+        matches
+    }
+
+    /// Get the variables of the pattern.
+    ///
+    /// The variables are ordered by their first occurrence in the query
+    /// and do not include the leading `#`.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    fn search<'a>(
+        &'a self,
+        graph: &DepGraph,
+        candidates: &[Vec<usize>],
+        relations: &[Vec<(usize, &Relation, usize)>],
+        tokens: &mut Vec<usize>,
+        matches: &mut Vec<Match<'a>>,
+    ) {
+        let var = tokens.len();
+        if var == self.variables.len() {
+            matches.push(Match {
+                variables: &self.variables,
+                tokens: tokens.clone(),
+            });
+            return;
+        }
+
+        for &token in &candidates[var] {
+            if tokens.contains(&token) {
+                continue;
+            }
+
+            tokens.push(token);
+            if relations[var].iter().all(|&(first, relation, second)| {
+                relation.holds(graph, tokens[first], tokens[second])
+            }) {
+                self.search(graph, candidates, relations, tokens, matches);
+            }
+            tokens.pop();
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        Ok(Parser::new(query).parse()?)
+    }
+}
+
+/// Binding of pattern variables to tokens.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Match<'a> {
+    variables: &'a [String],
+    tokens: Vec<usize>,
+}
+
+impl<'a> Match<'a> {
+    /// Get the token that is bound to `variable`.
+    ///
+    /// The variable is given without the leading `#`. Returns `None` if
+    /// the pattern does not have the variable.
+    pub fn get(&self, variable: &str) -> Option<usize> {
+        self.variables
+            .iter()
+            .position(|name| name == variable)
+            .map(|var| self.tokens[var])
+    }
+
+    /// Return an iterator over the variables and their tokens.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, usize)> + '_ {
+        self.variables
+            .iter()
+            .map(String::as_str)
+            .zip(self.tokens.iter().cloned())
+    }
+
+    /// Get the bound tokens, in the order of `Pattern::variables`.
+    pub fn tokens(&self) -> &[usize] {
+        &self.tokens
+    }
+}
+
+/// Recursive descent parser for queries.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    pattern: Pattern,
+}
+
+impl Parser {
+    fn new(query: &str) -> Self {
+        Parser {
+            chars: query.chars().collect(),
+            pos: 0,
+            pattern: Pattern {
+                variables: Vec::new(),
+                constraints: Vec::new(),
+                relations: Vec::new(),
+            },
+        }
+    }
+
+    fn parse(mut self) -> Result<Pattern, QueryError> {
+        loop {
+            self.clause()?;
+            self.skip_whitespace();
+            if self.pos == self.chars.len() {
+                return Ok(self.pattern);
+            }
+            self.expect('&')?;
+        }
+    }
+
+    fn clause(&mut self) -> Result<(), QueryError> {
+        let first = self.operand()?;
+
+        self.skip_whitespace();
+        let relation = match self.peek() {
+            Some('>') => {
+                self.pos += 1;
+                match self.peek() {
+                    Some('*') => {
+                        self.pos += 1;
+                        Relation::Descendant
+                    }
+                    Some('"') => Relation::Dependent(Some(self.string()?)),
+                    Some(c) if is_label_char(c) => Relation::Dependent(Some(self.label())),
+                    _ => Relation::Dependent(None),
+                }
+            }
+            Some('.') => {
+                self.pos += 1;
+                if self.peek() == Some('*') {
+                    self.pos += 1;
+                    Relation::Precedes
+                } else {
+                    Relation::ImmediatelyPrecedes
+                }
+            }
+            _ => return Ok(()),
+        };
+
+        let second = self.operand()?;
+        self.pattern.relations.push((first, relation, second));
+
+        Ok(())
+    }
+
+    /// Parse a variable with optional constraints, returning the
+    /// index of the variable.
+    fn operand(&mut self) -> Result<usize, QueryError> {
+        self.skip_whitespace();
+        self.expect('#')?;
+        let name = self.identifier()?;
+        let var = match self.pattern.variables.iter().position(|v| *v == name) {
+            Some(var) => var,
+            None => {
+                self.pattern.variables.push(name);
+                self.pattern.constraints.push(Vec::new());
+                self.pattern.variables.len() - 1
+            }
+        };
+
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            self.skip_whitespace();
+            self.expect('[')?;
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+            } else {
+                loop {
+                    let constraint = self.constraint()?;
+                    self.pattern.constraints[var].push(constraint);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some('&') => self.pos += 1,
+                        _ => return Err(self.error("expected '&' or ']'")),
+                    }
+                }
+            }
+        }
+
+        Ok(var)
+    }
+
+    fn constraint(&mut self) -> Result<Constraint, QueryError> {
+        self.skip_whitespace();
+        let position = self.pos;
+        let name = self.identifier()?;
+        let field = match name.as_str() {
+            "form" => Field::Form,
+            "lemma" => Field::Lemma,
+            "cpos" => Field::CPos,
+            "pos" => Field::Pos,
+            "feat" | "misc" => {
+                self.expect('.')?;
+                let key = if self.peek() == Some('"') {
+                    self.string()?
+                } else {
+                    self.identifier()?
+                };
+                if name == "feat" {
+                    Field::Feature(key)
+                } else {
+                    Field::Misc(key)
+                }
+            }
+            _ => {
+                return Err(QueryError::UnknownField {
+                    position,
+                    value: name,
+                })
+            }
+        };
+
+        self.skip_whitespace();
+        let negated = self.peek() == Some('!');
+        if negated {
+            self.pos += 1;
+        }
+        self.expect('=')?;
+        self.skip_whitespace();
+        let value = self.string()?;
+
+        Ok(Constraint {
+            field,
+            negated,
+            value,
+        })
+    }
+
+    fn error(&self, message: &str) -> QueryError {
+        QueryError::Syntax {
+            position: self.pos,
+            value: message.to_owned(),
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), QueryError> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .map(|c| c.is_alphanumeric() || c == '_')
+            .unwrap_or(false)
+        {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            Err(self.error("expected identifier"))
+        } else {
+            Ok(self.chars[start..self.pos].iter().collect())
+        }
+    }
+
+    fn label(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().map(is_label_char).unwrap_or(false) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map(char::is_whitespace).unwrap_or(false) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse a quoted string. Quotes and backslashes in the string are
+    /// escaped with a backslash.
+    fn string(&mut self) -> Result<String, QueryError> {
+        self.expect('"')?;
+
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c) if c == '"' || c == '\\' => value.push(c),
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                Some(c) => value.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+/// Check whether a character can occur in an unquoted relation.
+fn is_label_char(c: char) -> bool {
+    !c.is_whitespace() && c != '#' && c != '&' && c != '"' && c != '*'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+    use crate::error::{Error, QueryError};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::read_sentences;
+    use crate::token::{Features, TokenBuilder};
+
+    fn sentence() -> Sentence {
+        // Den Nachweis hat Beckmeyer bisher nicht geliefert .
+        let mut sentence = Sentence::new();
+        for &(form, lemma, pos, features) in &[
+            ("Den", "der", "ART", "case:acc|number:sg"),
+            ("Nachweis", "Nachweis", "NN", "case:acc|number:sg"),
+            ("hat", "haben", "VAFIN", "number:sg|tense:pres"),
+            ("Beckmeyer", "Beckmeyer", "NE", "case:nom|number:sg"),
+            ("bisher", "bisher", "ADV", "_"),
+            ("nicht", "nicht", "PTKNEG", "_"),
+            ("geliefert", "liefern", "VVPP", "_"),
+            (".", ".", "$.", "_"),
+        ] {
+            let mut builder = TokenBuilder::new(form).lemma(lemma).pos(pos);
+            if features != "_" {
+                builder = builder.features(Features::from(features));
+            }
+            sentence.push(builder.into());
+        }
+
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("DET"), 1));
+        graph.add_deprel(DepTriple::new(7, Some("OBJA"), 2));
+        graph.add_deprel(DepTriple::new(0, Some("ROOT"), 3));
+        graph.add_deprel(DepTriple::new(3, Some("SUBJ"), 4));
+        graph.add_deprel(DepTriple::new(7, Some("ADV"), 5));
+        graph.add_deprel(DepTriple::new(7, Some("ADV"), 6));
+        graph.add_deprel(DepTriple::new(3, Some("AUX"), 7));
+        graph.add_deprel(DepTriple::new(7, Some("-PUNCT-"), 8));
+
+        sentence
+    }
+
+    fn bindings(query: &str, sentence: &Sentence) -> Vec<Vec<usize>> {
+        let pattern: Pattern = query.parse().unwrap();
+        pattern
+            .matches(sentence)
+            .iter()
+            .map(|m| m.tokens().to_owned())
+            .collect()
+    }
+
+    fn parse_error(query: &str) -> QueryError {
+        match query.parse::<Pattern>() {
+            Err(Error::Query(err)) => err,
+            Err(err) => panic!("Unexpected error: {}", err),
+            Ok(_) => panic!("Query should not parse: {}", query),
+        }
+    }
+
+    #[test]
+    fn token_constraints() {
+        let sentence = sentence();
+        assert_eq!(bindings(r#"#n:[pos="NN"]"#, &sentence), vec![vec![2]]);
+        assert_eq!(
+            bindings(r#"#t:[feat.case="acc" & form!="Den"]"#, &sentence),
+            vec![vec![2]]
+        );
+        assert_eq!(
+            bindings(
+                r#"#t:[lemma = "haben"] & #t:[feat.tense="pres"]"#,
+                &sentence
+            ),
+            vec![vec![3]]
+        );
+        assert_eq!(
+            bindings(r#"#t:[feat.case!="acc" & pos!="$."]"#, &sentence),
+            vec![vec![3], vec![4], vec![5], vec![6], vec![7]]
+        );
+        assert_eq!(bindings("#t:[]", &sentence).len(), 8);
+        assert!(bindings(r#"#t:[cpos="N"]"#, &sentence).is_empty());
+    }
+
+    #[test]
+    fn relations() {
+        let sentence = sentence();
+
+        // A finite verb with an accusative object to its left.
+        assert_eq!(
+            bindings(r#"#v:[pos="VVPP"] >OBJA #o & #o .* #v"#, &sentence),
+            vec![vec![7, 2]]
+        );
+        assert_eq!(
+            bindings(r#"#v:[pos="VVPP"] >ADV #a"#, &sentence),
+            vec![vec![7, 5], vec![7, 6]]
+        );
+        assert_eq!(
+            bindings(r#"#h >"-PUNCT-" #p"#, &sentence),
+            bindings("#h >-PUNCT- #p", &sentence)
+        );
+        assert_eq!(
+            bindings(r#"#h:[pos="VAFIN"] > #d"#, &sentence),
+            vec![vec![3, 4], vec![3, 7]]
+        );
+        assert_eq!(
+            bindings(r#"#h:[pos="VAFIN"] >* #d:[pos="ART"]"#, &sentence),
+            vec![vec![3, 1]]
+        );
+        assert_eq!(
+            bindings(r#"#a:[pos="ADV"] . #b"#, &sentence),
+            vec![vec![5, 6]]
+        );
+
+        // Variables are bound to different tokens.
+        assert!(bindings("#a . #b & #b . #a", &sentence).is_empty());
+        assert_eq!(bindings("#a >ADV #b & #a >ADV #c", &sentence).len(), 2);
+    }
+
+    #[test]
+    fn matches() {
+        let sentence = sentence();
+        let pattern: Pattern = "#h >SUBJ #s".parse().unwrap();
+        assert_eq!(pattern.variables(), &["h", "s"]);
+
+        let matches = pattern.matches(&sentence);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].get("h"), Some(3));
+        assert_eq!(matches[0].get("s"), Some(4));
+        assert_eq!(matches[0].get("x"), None);
+        assert_eq!(
+            matches[0].iter().collect::<Vec<_>>(),
+            vec![("h", 3), ("s", 4)]
+        );
+    }
+
+    #[test]
+    fn dominance_in_cyclic_graph() {
+        let mut sentence = read_sentences("testdata/nonprojective.conll").remove(0);
+        sentence
+            .dep_graph_mut()
+            .add_deprel(DepTriple::new(2, Some("cycle"), 1));
+
+        assert!(bindings("#a >* #b & #b >* #a", &sentence).len() > 1);
+        assert!(bindings("#a >* #b", &sentence)
+            .iter()
+            .all(|tokens| tokens[0] != 9 || tokens[1] != 1));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(
+            parse_error(r#"#v:[pos="VVFIN""#),
+            QueryError::Syntax {
+                position: 15,
+                value: "expected '&' or ']'".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error(r#"#v:[pos="VVFIN]"#),
+            QueryError::Syntax {
+                position: 15,
+                value: "unterminated string".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error("v > #w"),
+            QueryError::Syntax {
+                position: 0,
+                value: "expected '#'".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error("#v >"),
+            QueryError::Syntax {
+                position: 4,
+                value: "expected '#'".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error("#v #w"),
+            QueryError::Syntax {
+                position: 3,
+                value: "expected '&'".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error(""),
+            QueryError::Syntax {
+                position: 0,
+                value: "expected '#'".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_error(r#"#v:[tag="NN"]"#),
+            QueryError::UnknownField {
+                position: 4,
+                value: "tag".to_owned()
+            }
+        );
+    }
+}
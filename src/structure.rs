@@ -0,0 +1,360 @@
+//! Structural measures of dependency trees.
+//!
+//! This module computes measures that characterize mildly non-projective
+//! dependency trees, following *Mildly Non-Projective Dependency
+//! Structures*, Kuhlmann and Nivre, 2006:
+//!
+//! * Gap degree: the number of gaps in the yield of a token. The yield of
+//!   a token is the set of tokens that it dominates (including itself). A
+//!   gap is a maximal range of tokens between the tokens of a yield that
+//!   is not part of the yield.
+//! * Edge degree: the number of connected components in the span of a
+//!   relation that are not dominated by the head of the relation.
+//! * Well-nestedness: a tree is well-nested when no two disjoint subtrees
+//!   interleave.
+//! * Planarity: a tree is planar when no two relations cross. Relations
+//!   that are headed by the root are not considered.
+//!
+//! The root (index 0) is treated as a token that precedes all other
+//! tokens. The measures are computed on a single layer of a sentence
+//! (`Sentence::dep_graph` or `Sentence::proj_dep_graph`). The layer
+//! should be a tree, tokens that are not connected to the root are not
+//! part of any yield.
+
+use std::cmp::{max, min};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::graph::{DepGraph, DepTriple};
+
+/// Structural measures of a dependency tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Measures {
+    /// The gap degree of the tree, the maximum gap degree of its tokens.
+    pub gap_degree: usize,
+
+    /// The edge degree of the tree, the maximum edge degree of its
+    /// relations.
+    pub edge_degree: usize,
+
+    /// Whether the tree is well-nested.
+    pub well_nested: bool,
+
+    /// Whether the tree is planar.
+    pub planar: bool,
+}
+
+/// Compute the structural measures of a dependency tree.
+///
+/// ```
+/// use conllx::graph::{DepTriple, Sentence};
+/// use conllx::structure::{measures, Measures};
+/// use conllx::token::Token;
+///
+/// let mut sentence = Sentence::new();
+/// for form in &["A", "hearing", "is", "scheduled", "on", "the", "issue"] {
+///     sentence.push(Token::new(*form));
+/// }
+///
+/// let mut graph = sentence.dep_graph_mut();
+/// for &(head, dependent) in &[(2, 1), (3, 2), (0, 3), (3, 4), (2, 5), (7, 6), (5, 7)] {
+///     graph.add_deprel(DepTriple::new(head, Some("dep"), dependent));
+/// }
+///
+/// assert_eq!(
+///     measures(&sentence.dep_graph()),
+///     Measures {
+///         gap_degree: 1,
+///         edge_degree: 1,
+///         well_nested: true,
+///         planar: true,
+///     }
+/// );
+/// ```
+pub fn measures(graph: &DepGraph) -> Measures {
+    let yields = yields(graph);
+
+    Measures {
+        gap_degree: yields.iter().map(|y| gap_degree(y)).max().unwrap_or(0),
+        edge_degree: edge_degrees_impl(graph, &yields)
+            .into_iter()
+            .map(|(_, degree)| degree)
+            .max()
+            .unwrap_or(0),
+        well_nested: is_well_nested_impl(&yields),
+        planar: is_planar(graph),
+    }
+}
+
+/// Get the gap degree of every token.
+///
+/// The gap degree of the token with index *i* is at index *i* of the
+/// returned vector. Tokens that are not connected to the root have gap
+/// degree 0.
+pub fn gap_degrees(graph: &DepGraph) -> Vec<usize> {
+    yields(graph).iter().map(|y| gap_degree(y)).collect()
+}
+
+/// Get the edge degree of every relation.
+///
+/// The relations are returned in the order of their dependents.
+pub fn edge_degrees<'a>(graph: &'a DepGraph<'_>) -> Vec<(DepTriple<&'a str>, usize)> {
+    edge_degrees_impl(graph, &yields(graph))
+}
+
+/// Check whether a dependency tree is well-nested.
+pub fn is_well_nested(graph: &DepGraph) -> bool {
+    is_well_nested_impl(&yields(graph))
+}
+
+/// Check whether a dependency tree is planar.
+///
+/// Relations that are headed by the root are not considered, so a tree
+/// can be planar while it is not projective.
+pub fn is_planar(graph: &DepGraph) -> bool {
+    let spans = (1..graph.len())
+        .filter_map(|dependent| graph.head(dependent))
+        .filter(|triple| triple.head() != 0)
+        .map(|triple| span(&triple))
+        .collect::<Vec<_>>();
+
+    for (idx, &(start, end)) in spans.iter().enumerate() {
+        for &(other_start, other_end) in &spans[idx + 1..] {
+            if (start < other_start && other_start < end && end < other_end)
+                || (other_start < start && start < other_end && other_end < end)
+            {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn edge_degrees_impl<'a>(
+    graph: &'a DepGraph<'_>,
+    yields: &[Vec<usize>],
+) -> Vec<(DepTriple<&'a str>, usize)> {
+    let mut degrees = Vec::new();
+    for dependent in 1..graph.len() {
+        let triple = match graph.head(dependent) {
+            Some(triple) => triple,
+            None => continue,
+        };
+
+        // Every component that is not dominated by the head has exactly
+        // one token of which the head is outside the span, the root of
+        // the component.
+        let (start, end) = span(&triple);
+        let degree = (start + 1..end)
+            .filter(|&token| yields[triple.head()].binary_search(&token).is_err())
+            .filter(|&token| match graph.head(token) {
+                Some(head) => head.head() < start || head.head() > end,
+                None => true,
+            })
+            .count();
+
+        degrees.push((triple, degree));
+    }
+
+    degrees
+}
+
+fn is_well_nested_impl(yields: &[Vec<usize>]) -> bool {
+    for (token, token_yield) in yields.iter().enumerate() {
+        for (other, other_yield) in yields.iter().enumerate().skip(token + 1) {
+            if token_yield.binary_search(&other).is_ok()
+                || other_yield.binary_search(&token).is_ok()
+            {
+                continue;
+            }
+
+            if interleave(token_yield, other_yield) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Check whether two disjoint yields interleave.
+///
+/// Two yields interleave when there are tokens *a1 < b1 < a2 < b2*, such
+/// that *a1* and *a2* are in one yield and *b1* and *b2* in the other.
+fn interleave(yield_a: &[usize], yield_b: &[usize]) -> bool {
+    let (mut idx_a, mut idx_b) = (0, 0);
+    let mut last = None;
+    let mut switches = 0;
+
+    while idx_a < yield_a.len() && idx_b < yield_b.len() {
+        let from_a = yield_a[idx_a] < yield_b[idx_b];
+        if from_a {
+            idx_a += 1;
+        } else {
+            idx_b += 1;
+        }
+
+        if last.is_some() && last != Some(from_a) {
+            switches += 1;
+            if switches == 3 {
+                return true;
+            }
+        }
+        last = Some(from_a);
+    }
+
+    // The remainder of the longer yield can add one more switch.
+    switches == 2 && last.map(|from_a| from_a != (idx_a < yield_a.len())) == Some(true)
+}
+
+/// Get the number of gaps in a sorted yield.
+fn gap_degree(token_yield: &[usize]) -> usize {
+    token_yield
+        .windows(2)
+        .filter(|pair| pair[1] != pair[0] + 1)
+        .count()
+}
+
+/// Get the start and end of the span of a relation.
+fn span<S>(triple: &DepTriple<S>) -> (usize, usize) {
+    (
+        min(triple.head(), triple.dependent()),
+        max(triple.head(), triple.dependent()),
+    )
+}
+
+/// Get the sorted yield of every token.
+///
+/// Tokens that are not connected to the root have an empty yield.
+fn yields(graph: &DepGraph) -> Vec<Vec<usize>> {
+    let mut dependents = vec![Vec::new(); graph.len()];
+    for dependent in 1..graph.len() {
+        if let Some(triple) = graph.head(dependent) {
+            dependents[triple.head()].push(dependent);
+        }
+    }
+
+    let mut pre_order = Vec::with_capacity(graph.len());
+    let mut stack = vec![0];
+    while let Some(token) = stack.pop() {
+        pre_order.push(token);
+        stack.extend(&dependents[token]);
+    }
+
+    // Process tokens in reverse pre-order, so that the yields of the
+    // dependents of a token are known.
+    let mut yields = vec![Vec::new(); graph.len()];
+    for &token in pre_order.iter().rev() {
+        let mut token_yield = vec![token];
+        for &dependent in &dependents[token] {
+            token_yield.extend_from_slice(&yields[dependent]);
+        }
+        token_yield.sort_unstable();
+        yields[token] = token_yield;
+    }
+
+    yields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edge_degrees, gap_degrees, is_planar, is_well_nested, measures, Measures};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::proj::non_projective_deprels;
+    use crate::tests::read_sentences;
+    use crate::token::Token;
+
+    fn sentence_with_heads(heads: &[usize]) -> Sentence {
+        let mut sentence = Sentence::new();
+        for _ in heads {
+            sentence.push(Token::new("x"));
+        }
+
+        for (dependent, &head) in heads.iter().enumerate() {
+            sentence
+                .dep_graph_mut()
+                .add_deprel(DepTriple::new(head, Some("dep"), dependent + 1));
+        }
+
+        sentence
+    }
+
+    #[test]
+    fn projective_trees() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            assert_eq!(
+                measures(&sentence.dep_graph()),
+                Measures {
+                    gap_degree: 0,
+                    edge_degree: 0,
+                    well_nested: true,
+                    planar: true,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn gap_degree() {
+        let sentence = sentence_with_heads(&[5, 5, 1, 2, 0]);
+        assert_eq!(gap_degrees(&sentence.dep_graph()), vec![0, 1, 1, 0, 0, 0]);
+
+        let sentence = sentence_with_heads(&[2, 0, 1, 2, 1]);
+        assert_eq!(gap_degrees(&sentence.dep_graph()), vec![0, 2, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn edge_degree() {
+        let sentence = sentence_with_heads(&[5, 5, 1, 2, 0]);
+        let degrees = edge_degrees(&sentence.dep_graph())
+            .into_iter()
+            .map(|(triple, degree)| (triple.head(), triple.dependent(), degree))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            degrees,
+            vec![(5, 1, 0), (5, 2, 0), (1, 3, 1), (2, 4, 1), (0, 5, 0)]
+        );
+
+        // Tokens 2 and 4 are not dominated by 1, but form a single
+        // component.
+        let sentence = sentence_with_heads(&[2, 0, 1, 2, 1]);
+        let graph = sentence.dep_graph();
+        let degrees = edge_degrees(&graph);
+        assert_eq!(degrees[4].0, DepTriple::new(1, Some("dep"), 5));
+        assert_eq!(degrees[4].1, 1);
+    }
+
+    #[test]
+    fn well_nestedness() {
+        // The yields of 1 (1, 3) and 2 (2, 4) interleave.
+        let sentence = sentence_with_heads(&[5, 5, 1, 2, 0]);
+        assert!(!is_well_nested(&sentence.dep_graph()));
+
+        // The yields of 1 (1, 4) and 2 (2, 3) are nested.
+        let sentence = sentence_with_heads(&[5, 5, 2, 1, 0]);
+        assert!(is_well_nested(&sentence.dep_graph()));
+
+        for sentence in read_sentences("testdata/nonprojective.conll") {
+            assert!(is_well_nested(&sentence.dep_graph()));
+        }
+    }
+
+    #[test]
+    fn planarity() {
+        let sentence = sentence_with_heads(&[5, 5, 1, 2, 0]);
+        assert!(!is_planar(&sentence.dep_graph()));
+
+        // Relations that share a token do not cross.
+        let sentence = sentence_with_heads(&[0, 1, 1]);
+        assert!(is_planar(&sentence.dep_graph()));
+
+        // The relation 3 -> 1 is not projective, since 2 is not dominated
+        // by 3. It only crosses the relation of the root 0 -> 2.
+        let sentence = sentence_with_heads(&[3, 0, 2]);
+        assert!(!non_projective_deprels(&sentence.dep_graph()).is_empty());
+        assert!(is_planar(&sentence.dep_graph()));
+    }
+}
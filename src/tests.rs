@@ -6,6 +6,7 @@ use lazy_static::lazy_static;
 use crate::graph::{DepTriple, Sentence};
 use crate::io::{ReadSentence, Reader};
 use crate::token::{Features, TokenBuilder};
+use crate::transition::{Configuration, DynamicOracle};
 
 lazy_static! {
     pub static ref TEST_SENTENCES: Vec<Sentence> = {
@@ -74,3 +75,43 @@ pub fn read_sentences(filename: &str) -> Vec<Sentence> {
         .map(|s| s.unwrap())
         .collect()
 }
+
+/// Check a dynamic oracle by parsing a sentence with exploration.
+///
+/// The sentence is parsed by following zero-cost transitions. Unless
+/// `seed` is 0, a random legal transition from `candidates` is followed
+/// in roughly one third of the steps. The number of tokens with an incorrect
+/// head or relation should be equal to the sum of the transition costs.
+pub fn explore_dynamic_oracle<S, F>(system: &S, sentence: &Sentence, seed: u64, candidates: F)
+where
+    S: DynamicOracle,
+    F: Fn(&Configuration) -> Vec<S::Transition>,
+{
+    let gold = sentence.dep_graph();
+    let mut config = Configuration::new(sentence);
+
+    let mut state = seed;
+    let mut cost = 0;
+    while !system.is_terminal(&config) {
+        // Linear congruential generator from Numerical Recipes.
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223) % (1 << 32);
+
+        let mut transitions = system.zero_cost_transitions(&config, &gold);
+        assert!(!transitions.is_empty(), "no zero-cost transitions");
+        if seed != 0 && state % 3 == 1 {
+            transitions = candidates(&config)
+                .into_iter()
+                .filter(|transition| system.is_legal(&config, transition))
+                .collect();
+        }
+
+        let transition = transitions.swap_remove((state >> 8) as usize % transitions.len());
+        cost += system.cost(&config, &transition, &gold);
+        system.apply(&mut config, &transition);
+    }
+
+    let errors = (1..sentence.len())
+        .filter(|&token| config.sentence().dep_graph().head(token) != gold.head(token))
+        .count();
+    assert_eq!(errors, cost);
+}
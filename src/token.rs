@@ -57,6 +57,12 @@ impl TokenBuilder {
         self.token.set_features(Some(features));
         self
     }
+
+    /// Set the miscellaneous annotations of the token.
+    pub fn misc(mut self, misc: Misc) -> TokenBuilder {
+        self.token.set_misc(Some(misc));
+        self
+    }
 }
 
 impl From<Token> for TokenBuilder {
@@ -78,6 +84,7 @@ pub struct Token {
     cpos: Option<String>,
     pos: Option<String>,
     features: Option<Features>,
+    misc: Option<Misc>,
 }
 
 impl Token {
@@ -89,6 +96,7 @@ impl Token {
             cpos: None,
             pos: None,
             features: None,
+            misc: None,
         }
     }
 
@@ -124,6 +132,18 @@ impl Token {
         self.features.as_mut()
     }
 
+    /// Get the miscellaneous annotations of the token.
+    pub fn misc(&self) -> Option<&Misc> {
+        self.misc.as_ref()
+    }
+
+    /// Get the miscellaneous annotations of the token.
+    ///
+    /// Returns a mutable reference, so that the annotations can be updated.
+    pub fn misc_mut(&mut self) -> Option<&mut Misc> {
+        self.misc.as_mut()
+    }
+
     /// Set the word form or punctuation symbol.
     ///
     /// Returns the form that is replaced.
@@ -167,6 +187,13 @@ impl Token {
     pub fn set_features(&mut self, features: Option<Features>) -> Option<Features> {
         mem::replace(&mut self.features, features)
     }
+
+    /// Set the miscellaneous annotations of the token.
+    ///
+    /// Returns the annotations that are replaced.
+    pub fn set_misc(&mut self, misc: Option<Misc>) -> Option<Misc> {
+        mem::replace(&mut self.misc, misc)
+    }
 }
 
 /// Token features.
@@ -190,21 +217,6 @@ impl Features {
     pub fn into_inner(self) -> BTreeMap<String, Option<String>> {
         self.inner
     }
-
-    fn parse_features(feature_string: impl AsRef<str>) -> BTreeMap<String, Option<String>> {
-        let mut features = BTreeMap::new();
-
-        for fv in feature_string.as_ref().split('|') {
-            let fv: &str = fv;
-            let (k, v) = fv
-                .find(':')
-                .map(|idx| (fv[..idx].to_owned(), Some(fv[idx + 1..].to_owned())))
-                .unwrap_or_else(|| (fv.to_owned(), None));
-            features.insert(k, v);
-        }
-
-        features
-    }
 }
 
 impl Default for Features {
@@ -243,7 +255,7 @@ impl From<BTreeMap<String, Option<String>>> for Features {
 impl From<&str> for Features {
     fn from(feature_string: &str) -> Self {
         Features {
-            inner: Features::parse_features(feature_string),
+            inner: parse_key_values(feature_string, ':'),
         }
     }
 }
@@ -272,17 +284,189 @@ impl From<Features> for String {
 
 impl From<&Features> for String {
     fn from(features: &Features) -> Self {
-        features
-            .inner
+        key_values_to_string(&features.inner, ':')
+    }
+}
+
+/// Miscellaneous token annotations.
+///
+/// These are the annotations of the MISC column of the CoNLL-U format,
+/// such as `SpaceAfter=No`. The annotations are a list of key-value
+/// pairs, where the value is optional. Unlike features, annotations are
+/// kept in their original order and keys can occur more than once, so
+/// that the MISC column is written back unchanged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Misc {
+    inner: Vec<(String, Option<String>)>,
+}
+
+impl Misc {
+    /// Construct an empty set of annotations.
+    pub fn new() -> Self {
+        Misc { inner: Vec::new() }
+    }
+
+    /// Get the value of the first annotation with key `key`.
+    ///
+    /// Returns `None` if there is no annotation with the key.
+    pub fn get(&self, key: &str) -> Option<&Option<String>> {
+        self.inner
             .iter()
-            .map(|(k, v)| match *v {
-                Some(ref v) => format!("{}:{}", k, v),
-                None => k.to_owned(),
-            })
-            .join("|")
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Set the value of the annotation with key `key`.
+    ///
+    /// The value of the first annotation with the key is replaced. If
+    /// there is no such annotation, the annotation is appended. Returns
+    /// the value that is replaced.
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: Option<String>,
+    ) -> Option<Option<String>> {
+        let key = key.into();
+        match self.inner.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, old_value)) => Some(mem::replace(old_value, value)),
+            None => {
+                self.inner.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Remove all annotations with key `key`.
+    ///
+    /// Returns the value of the first removed annotation.
+    pub fn remove(&mut self, key: &str) -> Option<Option<String>> {
+        let idx = self.inner.iter().position(|(k, _)| k == key)?;
+        let (_, value) = self.inner.remove(idx);
+        self.inner.retain(|(k, _)| k != key);
+        Some(value)
+    }
+
+    /// Unwrap the contained annotations.
+    pub fn into_inner(self) -> Vec<(String, Option<String>)> {
+        self.inner
+    }
+}
+
+impl Default for Misc {
+    fn default() -> Self {
+        Misc::new()
+    }
+}
+
+impl Deref for Misc {
+    type Target = Vec<(String, Option<String>)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Misc {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
     }
 }
 
+impl Display for Misc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let misc_str: String = self.into();
+        f.write_str(&misc_str)
+    }
+}
+
+impl From<BTreeMap<String, Option<String>>> for Misc {
+    fn from(misc_map: BTreeMap<String, Option<String>>) -> Self {
+        Misc {
+            inner: misc_map.into_iter().collect(),
+        }
+    }
+}
+
+impl From<Vec<(String, Option<String>)>> for Misc {
+    fn from(misc: Vec<(String, Option<String>)>) -> Self {
+        Misc { inner: misc }
+    }
+}
+
+impl From<&str> for Misc {
+    fn from(misc_string: &str) -> Self {
+        Misc {
+            inner: split_key_values(misc_string, '=').collect(),
+        }
+    }
+}
+
+impl<S, T> FromIterator<(S, Option<T>)> for Misc
+where
+    S: Into<String>,
+    T: Into<String>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (S, Option<T>)>,
+    {
+        Misc {
+            inner: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.map(Into::into)))
+                .collect(),
+        }
+    }
+}
+
+impl From<Misc> for String {
+    fn from(misc: Misc) -> Self {
+        (&misc).into()
+    }
+}
+
+impl From<&Misc> for String {
+    fn from(misc: &Misc) -> Self {
+        key_values_to_string(misc.inner.iter().map(|(k, v)| (k, v)), '=')
+    }
+}
+
+/// Parse `|`-separated key-value pairs, where keys and values are
+/// separated by `separator`.
+pub(crate) fn parse_key_values(
+    kv_string: impl AsRef<str>,
+    separator: char,
+) -> BTreeMap<String, Option<String>> {
+    split_key_values(kv_string.as_ref(), separator).collect()
+}
+
+/// Split `|`-separated key-value pairs, where keys and values are
+/// separated by `separator`, preserving their order.
+fn split_key_values(
+    kv_string: &str,
+    separator: char,
+) -> impl Iterator<Item = (String, Option<String>)> + '_ {
+    kv_string.split('|').map(move |kv| {
+        kv.find(separator)
+            .map(|idx| (kv[..idx].to_owned(), Some(kv[idx + 1..].to_owned())))
+            .unwrap_or_else(|| (kv.to_owned(), None))
+    })
+}
+
+/// Convert key-value pairs to a `|`-separated string, where keys and
+/// values are separated by `separator`.
+pub(crate) fn key_values_to_string<'a>(
+    kvs: impl IntoIterator<Item = (&'a String, &'a Option<String>)>,
+    separator: char,
+) -> String {
+    kvs.into_iter()
+        .map(|(k, v)| match *v {
+            Some(ref v) => format!("{}{}{}", k, separator, v),
+            None => k.to_owned(),
+        })
+        .join("|")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -291,7 +475,7 @@ mod tests {
     use maplit::btreemap;
     use quickcheck::quickcheck;
 
-    use super::{Features, Token, TokenBuilder};
+    use super::{Features, Misc, Token, TokenBuilder};
 
     quickcheck! {
         fn features_from_iter(feature_map: BTreeMap<String, Option<String>>) -> bool{
@@ -313,6 +497,41 @@ mod tests {
         assert_eq!(features_string, "feature1:x|feature2:y|feature3");
     }
 
+    #[test]
+    fn misc_from_string() {
+        let misc = Misc::from("SpaceAfter=No|Gloss=a=b|Flag|Gloss=c");
+        assert_eq!(
+            *misc,
+            vec![
+                ("SpaceAfter".to_owned(), Some("No".to_owned())),
+                ("Gloss".to_owned(), Some("a=b".to_owned())),
+                ("Flag".to_owned(), None),
+                ("Gloss".to_owned(), Some("c".to_owned())),
+            ]
+        );
+        assert_eq!(misc.get("Gloss"), Some(&Some("a=b".to_owned())));
+        assert_eq!(misc.get("Flag"), Some(&None));
+        assert_eq!(misc.get("Other"), None);
+
+        let misc_string: String = misc.into();
+        assert_eq!(misc_string, "SpaceAfter=No|Gloss=a=b|Flag|Gloss=c");
+    }
+
+    #[test]
+    fn misc_insert_remove() {
+        let mut misc = Misc::from("SpaceAfter=No|Gloss=a|Gloss=b");
+        assert_eq!(
+            misc.insert("Gloss", Some("c".to_owned())),
+            Some(Some("a".to_owned()))
+        );
+        assert_eq!(misc.insert("Flag", None), None);
+        assert_eq!(misc.to_string(), "SpaceAfter=No|Gloss=c|Gloss=b|Flag");
+
+        assert_eq!(misc.remove("Gloss"), Some(Some("c".to_owned())));
+        assert_eq!(misc.remove("Gloss"), None);
+        assert_eq!(misc.to_string(), "SpaceAfter=No|Flag");
+    }
+
     #[test]
     fn features_with_colons() {
         let f = "Some:feature:with|additional:colons|feature";
@@ -0,0 +1,208 @@
+//! Transition systems for dependency parsing.
+//!
+//! A transition system derives a dependency graph by applying transitions
+//! to a parser configuration. A configuration (`Configuration`) consists
+//! of a stack, a buffer and a partial sentence. The relations that are
+//! added by transitions are stored in the non-projective layer of the
+//! partial sentence (`Sentence::dep_graph`).
+//!
+//! Transition systems implement the `TransitionSystem` trait. Systems
+//! with a static oracle also implement `StaticOracle`, which derives the
+//! gold transition sequence of a sentence. Systems with a dynamic oracle
+//! implement `DynamicOracle`, which gives the optimal transitions in any
+//! configuration. Dynamic oracles can be used to train parsers with
+//! exploration, see: *Training Deterministic Parsers with Non-Deterministic
+//! Oracles*, Goldberg and Nivre, 2013.
+
+use std::collections::VecDeque;
+
+use crate::error::{Error, GraphError};
+use crate::graph::{DepGraph, DepTriple, Sentence};
+
+pub mod arc_eager;
+
+pub mod arc_hybrid;
+
+pub mod arc_standard;
+
+pub mod swap;
+
+/// Parser configuration.
+#[derive(Clone, Debug)]
+pub struct Configuration {
+    stack: Vec<usize>,
+    buffer: VecDeque<usize>,
+    sentence: Sentence,
+}
+
+impl Configuration {
+    /// Construct the initial configuration for a sentence.
+    ///
+    /// The stack of the initial configuration contains the root and the
+    /// buffer contains all tokens of the sentence. The partial sentence
+    /// is a copy of `sentence` without relations in the non-projective
+    /// layer.
+    pub fn new(sentence: &Sentence) -> Self {
+        let mut sentence = sentence.clone();
+        let mut graph = sentence.dep_graph_mut();
+        for dependent in 0..graph.len() {
+            graph.remove_head_rel(dependent);
+        }
+
+        Configuration {
+            stack: vec![0],
+            buffer: (1..sentence.len()).collect(),
+            sentence,
+        }
+    }
+
+    /// Get the buffer.
+    ///
+    /// The front of the buffer is the next token to be processed.
+    pub fn buffer(&self) -> &VecDeque<usize> {
+        &self.buffer
+    }
+
+    /// Get the stack.
+    ///
+    /// The last element is the top of the stack.
+    pub fn stack(&self) -> &[usize] {
+        &self.stack
+    }
+
+    /// Get the partial sentence.
+    pub fn sentence(&self) -> &Sentence {
+        &self.sentence
+    }
+
+    /// Unwrap the partial sentence.
+    pub fn into_sentence(self) -> Sentence {
+        self.sentence
+    }
+
+    /// Get the element of the stack at `depth`, where the top of the
+    /// stack has depth 0.
+    fn stack_at(&self, depth: usize) -> Option<usize> {
+        if depth < self.stack.len() {
+            Some(self.stack[self.stack.len() - depth - 1])
+        } else {
+            None
+        }
+    }
+
+    /// Check whether a token has a head in the partial sentence.
+    fn has_head(&self, token: usize) -> bool {
+        self.sentence.dep_graph().head(token).is_some()
+    }
+
+    /// Add a relation to the partial sentence.
+    fn add_relation(&mut self, head: usize, relation: Option<&str>, dependent: usize) {
+        self.sentence
+            .dep_graph_mut()
+            .add_deprel(DepTriple::new(head, relation, dependent));
+    }
+}
+
+/// Transition system.
+pub trait TransitionSystem {
+    /// The transitions of the system.
+    type Transition;
+
+    /// Check whether a configuration is terminal.
+    fn is_terminal(&self, config: &Configuration) -> bool;
+
+    /// Check whether a transition can be applied to a configuration.
+    fn is_legal(&self, config: &Configuration, transition: &Self::Transition) -> bool;
+
+    /// Apply a transition to a configuration.
+    ///
+    /// # Panics
+    ///
+    /// This method panics when the transition is not legal in the
+    /// configuration.
+    fn apply(&self, config: &mut Configuration, transition: &Self::Transition);
+}
+
+/// Static oracle.
+///
+/// A static oracle gives the next transition of the gold transition
+/// sequence of a sentence. The oracle can only be used in
+/// configurations that are reachable through the gold transition
+/// sequence.
+pub trait StaticOracle: TransitionSystem {
+    /// Get the next gold transition.
+    ///
+    /// Returns `None` when the gold graph cannot be derived from the
+    /// configuration.
+    fn next_transition(&self, config: &Configuration, gold: &DepGraph) -> Option<Self::Transition>;
+
+    /// Get the gold transition sequence of a sentence.
+    ///
+    /// An error is returned when the transition system cannot derive
+    /// the non-projective layer of the sentence.
+    fn transitions(&self, gold: &Sentence) -> Result<Vec<Self::Transition>, Error> {
+        let gold_graph = gold.dep_graph();
+        let mut config = Configuration::new(gold);
+
+        let mut transitions = Vec::new();
+        while !self.is_terminal(&config) {
+            let transition = self.next_transition(&config, &gold_graph).ok_or_else(|| {
+                GraphError::NotDerivable {
+                    value: format!(
+                        "no transition for stack {:?} and buffer {:?}",
+                        config.stack, config.buffer
+                    ),
+                }
+            })?;
+            self.apply(&mut config, &transition);
+            transitions.push(transition);
+        }
+
+        Ok(transitions)
+    }
+}
+
+/// Dynamic oracle.
+///
+/// A dynamic oracle gives the cost of a transition in any configuration.
+/// The cost of a transition is the number of gold relations (including
+/// their labels) that can be derived before, but not after applying the
+/// transition. For projective gold graphs, a transition with zero cost is
+/// available in every non-terminal configuration and the cost of a
+/// transition sequence is the number of tokens with an incorrect head or
+/// relation.
+pub trait DynamicOracle: TransitionSystem {
+    /// Get the cost of a legal transition.
+    fn cost(&self, config: &Configuration, transition: &Self::Transition, gold: &DepGraph)
+        -> usize;
+
+    /// Get the legal transitions with zero cost.
+    ///
+    /// The relation of an arc transition is the gold relation of its
+    /// dependent.
+    fn zero_cost_transitions(
+        &self,
+        config: &Configuration,
+        gold: &DepGraph,
+    ) -> Vec<Self::Transition>;
+}
+
+/// Get the gold head of a token.
+fn gold_head(gold: &DepGraph, token: usize) -> Option<usize> {
+    gold.head(token).map(|triple| triple.head())
+}
+
+/// Get the gold relation of a token.
+fn gold_relation(gold: &DepGraph, token: usize) -> Option<String> {
+    gold.head(token)
+        .and_then(|triple| triple.relation().map(ToOwned::to_owned))
+}
+
+/// Get the cost of attaching `dependent` to its gold head with an
+/// incorrect relation.
+fn relation_cost(gold: &DepGraph, head: usize, relation: Option<&str>, dependent: usize) -> usize {
+    match gold.head(dependent) {
+        Some(triple) if triple.head() == head && triple.relation() != relation => 1,
+        _ => 0,
+    }
+}
@@ -0,0 +1,266 @@
+//! Arc-eager transition system.
+//!
+//! See: *An Efficient Algorithm for Projective Dependency Parsing*, Nivre,
+//! 2003 and *A Dynamic Oracle for Arc-Eager Dependency Parsing*, Goldberg
+//! and Nivre, 2012.
+
+use crate::graph::DepGraph;
+use crate::transition::{
+    gold_head, gold_relation, relation_cost, Configuration, DynamicOracle, TransitionSystem,
+};
+
+/// Arc-eager transition.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Transition {
+    /// Move the first token of the buffer to the stack.
+    Shift,
+
+    /// Remove the token on top of the stack, which must have a head.
+    Reduce,
+
+    /// Attach the token on top of the stack to the first token of the
+    /// buffer and remove it from the stack.
+    LeftArc(Option<String>),
+
+    /// Attach the first token of the buffer to the token on top of the
+    /// stack and move it to the stack.
+    RightArc(Option<String>),
+}
+
+/// Arc-eager transition system.
+///
+/// In the arc-eager system, relations are added between the token on top
+/// of the stack and the first token of the buffer. Right dependents are
+/// attached as soon as possible and are only removed from the stack when
+/// they have collected their own dependents. The system can only derive
+/// projective trees. The root can receive multiple dependents.
+///
+/// The system terminates when the buffer is empty. Tokens on the stack
+/// that were not attached at that point do not have a head.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ArcEager;
+
+impl ArcEager {
+    pub fn new() -> Self {
+        ArcEager
+    }
+}
+
+impl Default for ArcEager {
+    fn default() -> Self {
+        ArcEager
+    }
+}
+
+impl TransitionSystem for ArcEager {
+    type Transition = Transition;
+
+    fn is_terminal(&self, config: &Configuration) -> bool {
+        config.buffer.is_empty()
+    }
+
+    fn is_legal(&self, config: &Configuration, transition: &Transition) -> bool {
+        let stack_top = config.stack_at(0);
+
+        match transition {
+            Transition::Shift => !config.buffer.is_empty(),
+            Transition::Reduce => match stack_top {
+                Some(top) => config.has_head(top),
+                None => false,
+            },
+            Transition::LeftArc(_) => match stack_top {
+                Some(top) => top != 0 && !config.has_head(top) && !config.buffer.is_empty(),
+                None => false,
+            },
+            Transition::RightArc(_) => stack_top.is_some() && !config.buffer.is_empty(),
+        }
+    }
+
+    fn apply(&self, config: &mut Configuration, transition: &Transition) {
+        assert!(
+            self.is_legal(config, transition),
+            "Illegal transition: {:?}",
+            transition
+        );
+
+        match transition {
+            Transition::Shift => {
+                let token = config.buffer.pop_front().unwrap();
+                config.stack.push(token);
+            }
+            Transition::Reduce => {
+                config.stack.pop();
+            }
+            Transition::LeftArc(relation) => {
+                let dependent = config.stack.pop().unwrap();
+                let head = config.buffer[0];
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+            Transition::RightArc(relation) => {
+                let head = config.stack_at(0).unwrap();
+                let dependent = config.buffer.pop_front().unwrap();
+                config.add_relation(head, relation.as_deref(), dependent);
+                config.stack.push(dependent);
+            }
+        }
+    }
+}
+
+impl DynamicOracle for ArcEager {
+    fn cost(&self, config: &Configuration, transition: &Transition, gold: &DepGraph) -> usize {
+        // Gold dependents of `head` on the stack that do not have a head yet.
+        let stack_dependents = |head| {
+            config
+                .stack
+                .iter()
+                .filter(|&&token| !config.has_head(token) && gold_head(gold, token) == Some(head))
+                .count()
+        };
+        let buffer_dependents = |head| {
+            gold.dependents(head)
+                .filter(|triple| config.buffer.contains(&triple.dependent()))
+                .count()
+        };
+
+        match transition {
+            Transition::Shift => {
+                let next = config.buffer[0];
+                let head_lost = match gold_head(gold, next) {
+                    Some(head) => config.stack.contains(&head),
+                    None => false,
+                };
+
+                head_lost as usize + stack_dependents(next)
+            }
+            Transition::Reduce => buffer_dependents(config.stack_at(0).unwrap()),
+            Transition::LeftArc(relation) => {
+                let top = config.stack_at(0).unwrap();
+                let next = config.buffer[0];
+                let head_lost = match gold_head(gold, top) {
+                    Some(head) => head != next && config.buffer.contains(&head),
+                    None => false,
+                };
+
+                head_lost as usize
+                    + buffer_dependents(top)
+                    + relation_cost(gold, next, relation.as_deref(), top)
+            }
+            Transition::RightArc(relation) => {
+                let top = config.stack_at(0).unwrap();
+                let next = config.buffer[0];
+                let head_lost = match gold_head(gold, next) {
+                    Some(head) => {
+                        head != top
+                            && (config.stack.contains(&head) || config.buffer.contains(&head))
+                    }
+                    None => false,
+                };
+
+                head_lost as usize
+                    + stack_dependents(next)
+                    + relation_cost(gold, top, relation.as_deref(), next)
+            }
+        }
+    }
+
+    fn zero_cost_transitions(&self, config: &Configuration, gold: &DepGraph) -> Vec<Transition> {
+        let mut candidates = vec![Transition::Shift, Transition::Reduce];
+        if let Some(top) = config.stack_at(0) {
+            candidates.push(Transition::LeftArc(gold_relation(gold, top)));
+        }
+        if let Some(&next) = config.buffer.front() {
+            candidates.push(Transition::RightArc(gold_relation(gold, next)));
+        }
+
+        candidates
+            .into_iter()
+            .filter(|transition| {
+                self.is_legal(config, transition) && self.cost(config, transition, gold) == 0
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArcEager, Transition};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::{explore_dynamic_oracle, read_sentences};
+    use crate::token::Token;
+    use crate::transition::{gold_relation, Configuration, DynamicOracle, TransitionSystem};
+
+    fn candidates(config: &Configuration, sentence: &Sentence) -> Vec<Transition> {
+        let gold = sentence.dep_graph();
+        let mut candidates = vec![
+            Transition::Shift,
+            Transition::Reduce,
+            Transition::LeftArc(Some("WRONG".to_owned())),
+            Transition::RightArc(Some("WRONG".to_owned())),
+        ];
+        if let Some(&top) = config.stack().last() {
+            candidates.push(Transition::LeftArc(gold_relation(&gold, top)));
+        }
+        if let Some(&next) = config.buffer().front() {
+            candidates.push(Transition::RightArc(gold_relation(&gold, next)));
+        }
+        candidates
+    }
+
+    #[test]
+    fn oracle_derives_projective_trees() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            explore_dynamic_oracle(&ArcEager::new(), &sentence, 0, |config| {
+                candidates(config, &sentence)
+            });
+        }
+    }
+
+    #[test]
+    fn oracle_costs_under_exploration() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            for seed in 1..20 {
+                explore_dynamic_oracle(&ArcEager::new(), &sentence, seed, |config| {
+                    candidates(config, &sentence)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn costs() {
+        // 1 <- 2 -> 3, 0 -> 2
+        let mut sentence = Sentence::new();
+        for form in &["a", "b", "c"] {
+            sentence.push(Token::new(*form));
+        }
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("l"), 1));
+        graph.add_deprel(DepTriple::new(0, Some("root"), 2));
+        graph.add_deprel(DepTriple::new(2, Some("r"), 3));
+
+        let system = ArcEager::new();
+        let gold = sentence.dep_graph();
+        let mut config = Configuration::new(&sentence);
+        system.apply(&mut config, &Transition::Shift);
+
+        assert_eq!(
+            system.zero_cost_transitions(&config, &gold),
+            vec![Transition::LeftArc(Some("l".to_owned()))]
+        );
+
+        // Attaching 2 to 1 loses 1 <- 2 and 0 -> 2.
+        assert_eq!(
+            system.cost(&config, &Transition::RightArc(Some("l".to_owned())), &gold),
+            2
+        );
+
+        // Shifting 2 loses 0 -> 2 and 1 <- 2.
+        assert_eq!(system.cost(&config, &Transition::Shift, &gold), 2);
+
+        // Correct head, incorrect relation.
+        assert_eq!(
+            system.cost(&config, &Transition::LeftArc(Some("r".to_owned())), &gold),
+            1
+        );
+    }
+}
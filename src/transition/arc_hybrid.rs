@@ -0,0 +1,238 @@
+//! Arc-hybrid transition system.
+//!
+//! See: *Dynamic Programming Algorithms for Transition-Based Dependency
+//! Parsers*, Kuhlmann, Gómez-Rodríguez and Satta, 2011 and *Training
+//! Deterministic Parsers with Non-Deterministic Oracles*, Goldberg and
+//! Nivre, 2013.
+
+use crate::graph::DepGraph;
+use crate::transition::{
+    gold_head, gold_relation, relation_cost, Configuration, DynamicOracle, TransitionSystem,
+};
+
+/// Arc-hybrid transition.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Transition {
+    /// Move the first token of the buffer to the stack.
+    Shift,
+
+    /// Attach the token on top of the stack to the first token of the
+    /// buffer and remove it from the stack.
+    LeftArc(Option<String>),
+
+    /// Attach the token on top of the stack to the second token on the
+    /// stack and remove it from the stack.
+    RightArc(Option<String>),
+}
+
+/// Arc-hybrid transition system.
+///
+/// The arc-hybrid system attaches left dependents as in the arc-eager
+/// system and right dependents as in the arc-standard system. A token is
+/// removed from the stack when it is attached. The system can only derive
+/// projective trees. The root can receive multiple dependents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ArcHybrid;
+
+impl ArcHybrid {
+    pub fn new() -> Self {
+        ArcHybrid
+    }
+}
+
+impl Default for ArcHybrid {
+    fn default() -> Self {
+        ArcHybrid
+    }
+}
+
+impl TransitionSystem for ArcHybrid {
+    type Transition = Transition;
+
+    fn is_terminal(&self, config: &Configuration) -> bool {
+        config.buffer.is_empty() && config.stack.len() == 1
+    }
+
+    fn is_legal(&self, config: &Configuration, transition: &Transition) -> bool {
+        match transition {
+            Transition::Shift => !config.buffer.is_empty(),
+            Transition::LeftArc(_) => match config.stack_at(0) {
+                Some(top) => top != 0 && !config.buffer.is_empty(),
+                None => false,
+            },
+            Transition::RightArc(_) => config.stack.len() >= 2,
+        }
+    }
+
+    fn apply(&self, config: &mut Configuration, transition: &Transition) {
+        assert!(
+            self.is_legal(config, transition),
+            "Illegal transition: {:?}",
+            transition
+        );
+
+        match transition {
+            Transition::Shift => {
+                let token = config.buffer.pop_front().unwrap();
+                config.stack.push(token);
+            }
+            Transition::LeftArc(relation) => {
+                let dependent = config.stack.pop().unwrap();
+                let head = config.buffer[0];
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+            Transition::RightArc(relation) => {
+                let dependent = config.stack.pop().unwrap();
+                let head = config.stack_at(0).unwrap();
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+        }
+    }
+}
+
+impl DynamicOracle for ArcHybrid {
+    fn cost(&self, config: &Configuration, transition: &Transition, gold: &DepGraph) -> usize {
+        let buffer_dependents = |head| {
+            gold.dependents(head)
+                .filter(|triple| config.buffer.contains(&triple.dependent()))
+                .count()
+        };
+
+        match transition {
+            Transition::Shift => {
+                // After shifting, the next token can only be attached to
+                // the token on top of the stack or to a token in the
+                // buffer. It cannot get dependents from the stack anymore.
+                let next = config.buffer[0];
+                let head_lost = match gold_head(gold, next) {
+                    Some(head) => Some(head) != config.stack_at(0) && config.stack.contains(&head),
+                    None => false,
+                };
+                let dependents_lost = config
+                    .stack
+                    .iter()
+                    .filter(|&&token| gold_head(gold, token) == Some(next))
+                    .count();
+
+                head_lost as usize + dependents_lost
+            }
+            Transition::LeftArc(relation) => {
+                let top = config.stack_at(0).unwrap();
+                let next = config.buffer[0];
+                let head_lost = match gold_head(gold, top) {
+                    Some(head) => {
+                        Some(head) == config.stack_at(1)
+                            || (head != next && config.buffer.contains(&head))
+                    }
+                    None => false,
+                };
+
+                head_lost as usize
+                    + buffer_dependents(top)
+                    + relation_cost(gold, next, relation.as_deref(), top)
+            }
+            Transition::RightArc(relation) => {
+                let top = config.stack_at(0).unwrap();
+                let second = config.stack_at(1).unwrap();
+                let head_lost = match gold_head(gold, top) {
+                    Some(head) => config.buffer.contains(&head),
+                    None => false,
+                };
+
+                head_lost as usize
+                    + buffer_dependents(top)
+                    + relation_cost(gold, second, relation.as_deref(), top)
+            }
+        }
+    }
+
+    fn zero_cost_transitions(&self, config: &Configuration, gold: &DepGraph) -> Vec<Transition> {
+        let mut candidates = vec![Transition::Shift];
+        if let Some(top) = config.stack_at(0) {
+            candidates.push(Transition::LeftArc(gold_relation(gold, top)));
+            candidates.push(Transition::RightArc(gold_relation(gold, top)));
+        }
+
+        candidates
+            .into_iter()
+            .filter(|transition| {
+                self.is_legal(config, transition) && self.cost(config, transition, gold) == 0
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArcHybrid, Transition};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::{explore_dynamic_oracle, read_sentences};
+    use crate::token::Token;
+    use crate::transition::{gold_relation, Configuration, DynamicOracle, TransitionSystem};
+
+    fn candidates(config: &Configuration, sentence: &Sentence) -> Vec<Transition> {
+        let mut candidates = vec![
+            Transition::Shift,
+            Transition::LeftArc(Some("WRONG".to_owned())),
+            Transition::RightArc(Some("WRONG".to_owned())),
+        ];
+        if let Some(&top) = config.stack().last() {
+            let relation = gold_relation(&sentence.dep_graph(), top);
+            candidates.push(Transition::LeftArc(relation.clone()));
+            candidates.push(Transition::RightArc(relation));
+        }
+        candidates
+    }
+
+    #[test]
+    fn oracle_derives_projective_trees() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            explore_dynamic_oracle(&ArcHybrid::new(), &sentence, 0, |config| {
+                candidates(config, &sentence)
+            });
+        }
+    }
+
+    #[test]
+    fn oracle_costs_under_exploration() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            for seed in 1..20 {
+                explore_dynamic_oracle(&ArcHybrid::new(), &sentence, seed, |config| {
+                    candidates(config, &sentence)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn costs() {
+        // 1 <- 2 -> 3, 0 -> 2
+        let mut sentence = Sentence::new();
+        for form in &["a", "b", "c"] {
+            sentence.push(Token::new(*form));
+        }
+        let mut graph = sentence.dep_graph_mut();
+        graph.add_deprel(DepTriple::new(2, Some("l"), 1));
+        graph.add_deprel(DepTriple::new(0, Some("root"), 2));
+        graph.add_deprel(DepTriple::new(2, Some("r"), 3));
+
+        let system = ArcHybrid::new();
+        let gold = sentence.dep_graph();
+        let mut config = Configuration::new(&sentence);
+        system.apply(&mut config, &Transition::Shift);
+
+        assert_eq!(
+            system.zero_cost_transitions(&config, &gold),
+            vec![Transition::LeftArc(Some("l".to_owned()))]
+        );
+
+        // Attaching 1 to the root loses 1 <- 2.
+        assert_eq!(
+            system.cost(&config, &Transition::RightArc(Some("l".to_owned())), &gold),
+            1
+        );
+
+        // Shifting 2 loses 0 -> 2 and 1 <- 2.
+        assert_eq!(system.cost(&config, &Transition::Shift, &gold), 2);
+    }
+}
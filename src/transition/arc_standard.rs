@@ -0,0 +1,222 @@
+//! Arc-standard transition system.
+//!
+//! See: *Incrementality in Deterministic Dependency Parsing*, Nivre, 2004.
+
+use crate::graph::DepGraph;
+use crate::transition::{Configuration, StaticOracle, TransitionSystem};
+
+/// Arc-standard transition.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Transition {
+    /// Move the first token of the buffer to the stack.
+    Shift,
+
+    /// Attach the second token on the stack to the token on top of the
+    /// stack and remove it from the stack.
+    LeftArc(Option<String>),
+
+    /// Attach the token on top of the stack to the second token on the
+    /// stack and remove it from the stack.
+    RightArc(Option<String>),
+}
+
+/// Arc-standard transition system.
+///
+/// In the arc-standard system, relations are added between the two
+/// topmost tokens of the stack. A dependent is removed from the stack
+/// when it is attached, so a token must have collected all of its
+/// dependents before it is attached to its head. The system can only
+/// derive projective trees. The root can only receive a dependent when
+/// the buffer is empty, so the derived trees have a single root
+/// dependent.
+///
+/// ```
+/// use conllx::graph::{DepTriple, Sentence};
+/// use conllx::token::Token;
+/// use conllx::transition::arc_standard::{ArcStandard, Transition};
+/// use conllx::transition::StaticOracle;
+///
+/// let mut sentence = Sentence::new();
+/// sentence.push(Token::new("Hello"));
+/// sentence.push(Token::new("world"));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(0, Some("ROOT"), 1));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(1, Some("OBJ"), 2));
+///
+/// assert_eq!(
+///     ArcStandard::new().transitions(&sentence).unwrap(),
+///     vec![
+///         Transition::Shift,
+///         Transition::Shift,
+///         Transition::RightArc(Some("OBJ".to_owned())),
+///         Transition::RightArc(Some("ROOT".to_owned())),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ArcStandard;
+
+impl ArcStandard {
+    pub fn new() -> Self {
+        ArcStandard
+    }
+}
+
+impl Default for ArcStandard {
+    fn default() -> Self {
+        ArcStandard
+    }
+}
+
+impl TransitionSystem for ArcStandard {
+    type Transition = Transition;
+
+    fn is_terminal(&self, config: &Configuration) -> bool {
+        config.buffer.is_empty() && config.stack.len() == 1
+    }
+
+    fn is_legal(&self, config: &Configuration, transition: &Transition) -> bool {
+        match transition {
+            Transition::Shift => !config.buffer.is_empty(),
+            Transition::LeftArc(_) => match config.stack_at(1) {
+                Some(second) => second != 0,
+                None => false,
+            },
+            Transition::RightArc(_) => match config.stack_at(1) {
+                Some(second) => second != 0 || config.buffer.is_empty(),
+                None => false,
+            },
+        }
+    }
+
+    fn apply(&self, config: &mut Configuration, transition: &Transition) {
+        assert!(
+            self.is_legal(config, transition),
+            "Illegal transition: {:?}",
+            transition
+        );
+
+        match transition {
+            Transition::Shift => {
+                let token = config.buffer.pop_front().unwrap();
+                config.stack.push(token);
+            }
+            Transition::LeftArc(relation) => {
+                let head = config.stack.pop().unwrap();
+                let dependent = config.stack.pop().unwrap();
+                config.stack.push(head);
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+            Transition::RightArc(relation) => {
+                let dependent = config.stack.pop().unwrap();
+                let head = config.stack_at(0).unwrap();
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+        }
+    }
+}
+
+impl StaticOracle for ArcStandard {
+    fn next_transition(&self, config: &Configuration, gold: &DepGraph) -> Option<Transition> {
+        if let (Some(first), Some(second)) = (config.stack_at(0), config.stack_at(1)) {
+            if let Some(triple) = gold.head(second) {
+                if second != 0 && triple.head() == first {
+                    return Some(Transition::LeftArc(
+                        triple.relation().map(ToOwned::to_owned),
+                    ));
+                }
+            }
+
+            // The token on top of the stack can only be attached when
+            // all its dependents are attached.
+            if let Some(triple) = gold.head(first) {
+                let transition = Transition::RightArc(triple.relation().map(ToOwned::to_owned));
+                if triple.head() == second
+                    && gold
+                        .dependents(first)
+                        .all(|triple| config.has_head(triple.dependent()))
+                    && self.is_legal(config, &transition)
+                {
+                    return Some(transition);
+                }
+            }
+        }
+
+        if config.buffer.is_empty() {
+            None
+        } else {
+            Some(Transition::Shift)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArcStandard, Transition};
+    use crate::graph::Sentence;
+    use crate::tests::read_sentences;
+    use crate::transition::{Configuration, StaticOracle, TransitionSystem};
+
+    fn parse(sentence: &Sentence, transitions: &[Transition]) -> Sentence {
+        let system = ArcStandard::new();
+        let mut config = Configuration::new(sentence);
+        for transition in transitions {
+            system.apply(&mut config, transition);
+        }
+        assert!(system.is_terminal(&config));
+        config.into_sentence()
+    }
+
+    #[test]
+    fn oracle_derives_projective_trees() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            let transitions = ArcStandard::new().transitions(&sentence).unwrap();
+            assert_eq!(transitions.len(), 2 * (sentence.len() - 1));
+
+            let parsed = parse(&sentence, &transitions);
+            for token in 0..sentence.len() {
+                assert_eq!(
+                    parsed.dep_graph().head(token),
+                    sentence.dep_graph().head(token)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oracle_rejects_non_projective_trees() {
+        for sentence in read_sentences("testdata/nonprojective.conll") {
+            assert!(ArcStandard::new().transitions(&sentence).is_err());
+        }
+    }
+
+    #[test]
+    fn legality() {
+        let system = ArcStandard::new();
+        let sentence = &read_sentences("testdata/projective.conll")[0];
+        let mut config = Configuration::new(sentence);
+        assert!(config.sentence().dep_graph().head(1).is_none());
+
+        assert!(system.is_legal(&config, &Transition::Shift));
+        assert!(!system.is_legal(&config, &Transition::LeftArc(None)));
+        assert!(!system.is_legal(&config, &Transition::RightArc(None)));
+
+        // The root cannot be a dependent or get a dependent while the
+        // buffer is not empty.
+        system.apply(&mut config, &Transition::Shift);
+        assert_eq!(config.stack(), &[0, 1]);
+        assert!(!system.is_legal(&config, &Transition::LeftArc(None)));
+        assert!(!system.is_legal(&config, &Transition::RightArc(None)));
+
+        system.apply(&mut config, &Transition::Shift);
+        assert!(system.is_legal(&config, &Transition::LeftArc(None)));
+        assert!(system.is_legal(&config, &Transition::RightArc(None)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn apply_illegal_transition() {
+        let sentence = &read_sentences("testdata/projective.conll")[0];
+        let mut config = Configuration::new(sentence);
+        ArcStandard::new().apply(&mut config, &Transition::LeftArc(None));
+    }
+}
@@ -0,0 +1,406 @@
+//! Arc-standard transition system with swap.
+//!
+//! See: *Non-Projective Dependency Parsing in Expected Linear Time*, Nivre,
+//! 2009 and *An Improved Oracle for Dependency Parsing with Online
+//! Reordering*, Nivre, Kuhlmann and Hall, 2009.
+
+use crate::error::{Error, GraphError};
+use crate::graph::{DepGraph, Sentence};
+use crate::transition::{Configuration, StaticOracle, TransitionSystem};
+
+/// Arc-standard with swap transition.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Transition {
+    /// Move the first token of the buffer to the stack.
+    Shift,
+
+    /// Attach the second token on the stack to the token on top of the
+    /// stack and remove it from the stack.
+    LeftArc(Option<String>),
+
+    /// Attach the token on top of the stack to the second token on the
+    /// stack and remove it from the stack.
+    RightArc(Option<String>),
+
+    /// Move the second token on the stack back to the buffer.
+    Swap,
+}
+
+/// Arc-standard transition system with swap.
+///
+/// This system extends the arc-standard system with a transition that
+/// moves the second token on the stack back to the buffer. This reorders
+/// the tokens, so that non-projective trees can be derived as projective
+/// trees over the reordered tokens. The root can only receive a
+/// dependent when the buffer is empty.
+///
+/// The static oracle reorders tokens into the projective order of the
+/// gold tree. Swaps are delayed as long as possible, by only swapping
+/// tokens when the first token of the buffer is not in the same maximal
+/// projective component as the token on top of the stack.
+///
+/// ```
+/// use conllx::graph::{DepTriple, Sentence};
+/// use conllx::token::Token;
+/// use conllx::transition::swap::{ArcStandardSwap, Transition};
+/// use conllx::transition::StaticOracle;
+///
+/// let mut sentence = Sentence::new();
+/// for form in &["A", "hearing", "is", "scheduled", "on", "the", "issue"] {
+///     sentence.push(Token::new(*form));
+/// }
+///
+/// let mut graph = sentence.dep_graph_mut();
+/// for &(head, dependent) in &[(2, 1), (3, 2), (0, 3), (3, 4), (2, 5), (7, 6), (5, 7)] {
+///     graph.add_deprel(DepTriple::new(head, Some("dep"), dependent));
+/// }
+///
+/// // "on the issue" is moved before "is" with a single swap.
+/// let transitions = ArcStandardSwap::new().transitions(&sentence).unwrap();
+/// assert_eq!(
+///     transitions.iter().filter(|&t| *t == Transition::Swap).count(),
+///     1
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ArcStandardSwap;
+
+impl ArcStandardSwap {
+    pub fn new() -> Self {
+        ArcStandardSwap
+    }
+
+    /// Get the next gold transition, given the projective order and the
+    /// maximal projective components of the gold tree.
+    fn next_transition_ordered(
+        self,
+        config: &Configuration,
+        gold: &DepGraph,
+        order: &[usize],
+        components: &[usize],
+    ) -> Option<Transition> {
+        let has_dependents = |head| {
+            gold.dependents(head)
+                .all(|triple| config.has_head(triple.dependent()))
+        };
+
+        if let (Some(first), Some(second)) = (config.stack_at(0), config.stack_at(1)) {
+            if let Some(triple) = gold.head(second) {
+                if second != 0 && triple.head() == first && has_dependents(second) {
+                    return Some(Transition::LeftArc(
+                        triple.relation().map(ToOwned::to_owned),
+                    ));
+                }
+            }
+
+            if let Some(triple) = gold.head(first) {
+                let transition = Transition::RightArc(triple.relation().map(ToOwned::to_owned));
+                if triple.head() == second
+                    && has_dependents(first)
+                    && self.is_legal(config, &transition)
+                {
+                    return Some(transition);
+                }
+            }
+
+            let same_component = match config.buffer.front() {
+                Some(&next) => components[first] == components[next],
+                None => false,
+            };
+            if order[first] < order[second]
+                && !same_component
+                && self.is_legal(config, &Transition::Swap)
+            {
+                return Some(Transition::Swap);
+            }
+        }
+
+        if config.buffer.is_empty() {
+            None
+        } else {
+            Some(Transition::Shift)
+        }
+    }
+}
+
+impl Default for ArcStandardSwap {
+    fn default() -> Self {
+        ArcStandardSwap
+    }
+}
+
+impl TransitionSystem for ArcStandardSwap {
+    type Transition = Transition;
+
+    fn is_terminal(&self, config: &Configuration) -> bool {
+        config.buffer.is_empty() && config.stack.len() == 1
+    }
+
+    fn is_legal(&self, config: &Configuration, transition: &Transition) -> bool {
+        match transition {
+            Transition::Shift => !config.buffer.is_empty(),
+            Transition::LeftArc(_) => match config.stack_at(1) {
+                Some(second) => second != 0,
+                None => false,
+            },
+            Transition::RightArc(_) => match config.stack_at(1) {
+                Some(second) => second != 0 || config.buffer.is_empty(),
+                None => false,
+            },
+            Transition::Swap => match (config.stack_at(0), config.stack_at(1)) {
+                (Some(first), Some(second)) => second != 0 && second < first,
+                _ => false,
+            },
+        }
+    }
+
+    fn apply(&self, config: &mut Configuration, transition: &Transition) {
+        assert!(
+            self.is_legal(config, transition),
+            "Illegal transition: {:?}",
+            transition
+        );
+
+        match transition {
+            Transition::Shift => {
+                let token = config.buffer.pop_front().unwrap();
+                config.stack.push(token);
+            }
+            Transition::LeftArc(relation) => {
+                let head = config.stack.pop().unwrap();
+                let dependent = config.stack.pop().unwrap();
+                config.stack.push(head);
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+            Transition::RightArc(relation) => {
+                let dependent = config.stack.pop().unwrap();
+                let head = config.stack_at(0).unwrap();
+                config.add_relation(head, relation.as_deref(), dependent);
+            }
+            Transition::Swap => {
+                let first = config.stack.pop().unwrap();
+                let second = config.stack.pop().unwrap();
+                config.stack.push(first);
+                config.buffer.push_front(second);
+            }
+        }
+    }
+}
+
+impl StaticOracle for ArcStandardSwap {
+    fn next_transition(&self, config: &Configuration, gold: &DepGraph) -> Option<Transition> {
+        self.next_transition_ordered(
+            config,
+            gold,
+            &projective_order(gold),
+            &projective_components(gold),
+        )
+    }
+
+    fn transitions(&self, gold: &Sentence) -> Result<Vec<Transition>, Error> {
+        // Compute the projective order and components once, rather than
+        // in every call of `next_transition`.
+        let gold_graph = gold.dep_graph();
+        let order = projective_order(&gold_graph);
+        let components = projective_components(&gold_graph);
+        let mut config = Configuration::new(gold);
+
+        let mut transitions = Vec::new();
+        while !self.is_terminal(&config) {
+            let transition = self
+                .next_transition_ordered(&config, &gold_graph, &order, &components)
+                .ok_or_else(|| GraphError::NotDerivable {
+                    value: format!(
+                        "no transition for stack {:?} and buffer {:?}",
+                        config.stack, config.buffer
+                    ),
+                })?;
+            self.apply(&mut config, &transition);
+            transitions.push(transition);
+        }
+
+        Ok(transitions)
+    }
+}
+
+/// Get the projective order of the tokens in a tree.
+///
+/// The projective order is the in-order traversal of the tree, where
+/// every token is placed between its left and right dependents. The
+/// position of a token in this order is at the index of the token in the
+/// returned vector. Tokens that are not connected to the root are
+/// placed after all other tokens.
+fn projective_order(graph: &DepGraph) -> Vec<usize> {
+    let mut dependents = vec![Vec::new(); graph.len()];
+    for dependent in 1..graph.len() {
+        if let Some(triple) = graph.head(dependent) {
+            dependents[triple.head()].push(dependent);
+        }
+    }
+
+    let mut order = vec![usize::MAX; graph.len()];
+    let mut position = 0;
+
+    // A stack item is a token and a flag that indicates whether its
+    // dependents were already put on the stack.
+    let mut stack = vec![(0, false)];
+    while let Some((token, expanded)) = stack.pop() {
+        if expanded {
+            order[token] = position;
+            position += 1;
+            continue;
+        }
+
+        // Push in reverse order: right dependents, the token, left
+        // dependents.
+        let (left, right): (Vec<_>, Vec<_>) =
+            dependents[token].iter().partition(|&&dep| dep < token);
+        stack.extend(right.into_iter().rev().map(|dep| (dep, false)));
+        stack.push((token, true));
+        stack.extend(left.into_iter().rev().map(|dep| (dep, false)));
+    }
+
+    order
+}
+
+/// Get the maximal projective components of a tree.
+///
+/// The components are found by deriving the tree with the arc-standard
+/// oracle without swaps, until no more relations can be added. Every
+/// token is assigned the token that dominates its component.
+fn projective_components(graph: &DepGraph) -> Vec<usize> {
+    let mut parents = (0..graph.len()).collect::<Vec<_>>();
+    let mut missing_dependents = (0..graph.len())
+        .map(|token| graph.dependents(token).count())
+        .collect::<Vec<_>>();
+
+    let mut stack = vec![0];
+    for token in 1..graph.len() {
+        stack.push(token);
+
+        while stack.len() >= 2 {
+            let first = stack[stack.len() - 1];
+            let second = stack[stack.len() - 2];
+
+            let (head, dependent) = if second != 0
+                && graph.head(second).map(|triple| triple.head()) == Some(first)
+                && missing_dependents[second] == 0
+            {
+                stack.remove(stack.len() - 2);
+                (first, second)
+            } else if graph.head(first).map(|triple| triple.head()) == Some(second)
+                && missing_dependents[first] == 0
+            {
+                stack.pop();
+                (second, first)
+            } else {
+                break;
+            };
+
+            parents[dependent] = head;
+            missing_dependents[head] -= 1;
+        }
+    }
+
+    (0..graph.len())
+        .map(|mut token| {
+            while parents[token] != token {
+                token = parents[token];
+            }
+            token
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{projective_components, projective_order, ArcStandardSwap, Transition};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::read_sentences;
+    use crate::token::Token;
+    use crate::transition::{Configuration, StaticOracle, TransitionSystem};
+
+    fn parse(sentence: &Sentence, transitions: &[Transition]) -> Sentence {
+        let system = ArcStandardSwap::new();
+        let mut config = Configuration::new(sentence);
+        for transition in transitions {
+            system.apply(&mut config, transition);
+        }
+        assert!(system.is_terminal(&config));
+        config.into_sentence()
+    }
+
+    fn hearing_sentence() -> Sentence {
+        let mut sentence = Sentence::new();
+        for form in &["A", "hearing", "is", "scheduled", "on", "the", "issue"] {
+            sentence.push(Token::new(*form));
+        }
+
+        let mut graph = sentence.dep_graph_mut();
+        for &(head, dependent) in &[(2, 1), (3, 2), (0, 3), (3, 4), (2, 5), (7, 6), (5, 7)] {
+            graph.add_deprel(DepTriple::new(head, Some("dep"), dependent));
+        }
+
+        sentence
+    }
+
+    #[test]
+    fn oracle_derives_trees() {
+        for sentence in read_sentences("testdata/nonprojective.conll")
+            .into_iter()
+            .chain(read_sentences("testdata/projective.conll"))
+        {
+            let transitions = ArcStandardSwap::new().transitions(&sentence).unwrap();
+            let parsed = parse(&sentence, &transitions);
+            for token in 0..sentence.len() {
+                assert_eq!(
+                    parsed.dep_graph().head(token),
+                    sentence.dep_graph().head(token)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oracle_does_not_swap_projective_trees() {
+        for sentence in read_sentences("testdata/projective.conll") {
+            assert!(!ArcStandardSwap::new()
+                .transitions(&sentence)
+                .unwrap()
+                .contains(&Transition::Swap));
+        }
+    }
+
+    #[test]
+    fn order_and_components() {
+        let sentence = hearing_sentence();
+        let graph = sentence.dep_graph();
+
+        // A hearing on the issue is scheduled
+        assert_eq!(projective_order(&graph), vec![0, 1, 2, 6, 7, 3, 4, 5]);
+
+        // [A hearing] [is scheduled] [on the issue]
+        assert_eq!(projective_components(&graph), vec![0, 2, 2, 3, 3, 5, 5, 5]);
+    }
+
+    #[test]
+    fn swap_legality() {
+        let system = ArcStandardSwap::new();
+        let sentence = hearing_sentence();
+        let mut config = Configuration::new(&sentence);
+
+        system.apply(&mut config, &Transition::Shift);
+        assert!(!system.is_legal(&config, &Transition::Swap));
+
+        system.apply(&mut config, &Transition::Shift);
+        assert!(system.is_legal(&config, &Transition::Swap));
+
+        system.apply(&mut config, &Transition::Swap);
+        assert_eq!(config.stack(), &[0, 2]);
+        assert_eq!(config.buffer().front(), Some(&1));
+
+        // Tokens cannot be swapped back.
+        system.apply(&mut config, &Transition::Shift);
+        assert!(!system.is_legal(&config, &Transition::Swap));
+    }
+}
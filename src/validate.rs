@@ -0,0 +1,344 @@
+//! Validation of dependency trees.
+
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools;
+
+use crate::graph::DepGraph;
+
+/// A violation of dependency tree well-formedness.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// The root node has a head.
+    RootHasHead,
+
+    /// A token does not have a head.
+    MissingHead { token: usize },
+
+    /// The root does not have the expected number of dependents.
+    RootDependents {
+        dependents: Vec<usize>,
+        expected: usize,
+    },
+
+    /// The head relations of the tokens form a cycle.
+    ///
+    /// The tokens of the cycle are sorted by their index.
+    Cycle { tokens: Vec<usize> },
+
+    /// A token is not connected to the root.
+    ///
+    /// This violation is only reported for tokens that have a head and
+    /// are not part of a cycle. Such tokens are attached to a token
+    /// without a head or to a cycle.
+    Disconnected { token: usize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Violation::RootHasHead => write!(f, "root has a head"),
+            Violation::MissingHead { token } => write!(f, "token {} does not have a head", token),
+            Violation::RootDependents {
+                dependents,
+                expected,
+            } => write!(
+                f,
+                "root has {} dependents ({}), expected {}",
+                dependents.len(),
+                dependents.iter().join(", "),
+                expected
+            ),
+            Violation::Cycle { tokens } => write!(f, "cycle: {}", tokens.iter().join(", ")),
+            Violation::Disconnected { token } => {
+                write!(f, "token {} is not connected to the root", token)
+            }
+        }
+    }
+}
+
+/// Validator for dependency trees.
+///
+/// The validator checks that a dependency graph is a tree: every token
+/// has a head, the root has the expected number of dependents, and all
+/// tokens are connected to the root without cycles. The validator works
+/// on both the non-projective and the projective layer:
+///
+/// ```
+/// use conllx::graph::{DepTriple, Sentence};
+/// use conllx::token::Token;
+/// use conllx::validate::{TreeValidator, Violation};
+///
+/// let mut sentence = Sentence::new();
+/// sentence.push(Token::new("Hello"));
+/// sentence.push(Token::new("world"));
+/// sentence.dep_graph_mut().add_deprel(DepTriple::new(0, Some("ROOT"), 1));
+///
+/// let validator = TreeValidator::new();
+/// assert_eq!(
+///     validator.validate(&sentence.dep_graph()),
+///     vec![Violation::MissingHead { token: 2 }]
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreeValidator {
+    root_dependents: usize,
+}
+
+impl TreeValidator {
+    /// Construct a validator that requires exactly one root dependent.
+    pub fn new() -> Self {
+        TreeValidator { root_dependents: 1 }
+    }
+
+    /// Set the number of dependents that the root should have.
+    pub fn root_dependents(mut self, root_dependents: usize) -> Self {
+        self.root_dependents = root_dependents;
+        self
+    }
+
+    /// Validate a dependency graph.
+    ///
+    /// Returns the violations of tree well-formedness. The graph is a
+    /// tree if no violations are returned. Graphs without tokens are
+    /// always valid.
+    pub fn validate(&self, graph: &DepGraph) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if graph.len() == 1 {
+            return violations;
+        }
+
+        if graph.head(0).is_some() {
+            violations.push(Violation::RootHasHead);
+        }
+
+        for token in 1..graph.len() {
+            if graph.head(token).is_none() {
+                violations.push(Violation::MissingHead { token });
+            }
+        }
+
+        let mut dependents = graph
+            .dependents(0)
+            .map(|triple| triple.dependent())
+            .collect::<Vec<_>>();
+        if dependents.len() != self.root_dependents {
+            dependents.sort_unstable();
+            violations.push(Violation::RootDependents {
+                dependents,
+                expected: self.root_dependents,
+            });
+        }
+
+        let (cycles, disconnected) = find_disconnected(graph);
+        violations.extend(cycles.into_iter().map(|tokens| Violation::Cycle { tokens }));
+        violations.extend(
+            disconnected
+                .into_iter()
+                .map(|token| Violation::Disconnected { token }),
+        );
+
+        violations
+    }
+}
+
+impl Default for TreeValidator {
+    fn default() -> Self {
+        TreeValidator::new()
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Visit {
+    Unvisited,
+    OnPath,
+    Connected,
+    Disconnected,
+    Cycle,
+}
+
+/// Find cycles and tokens that are not connected to the root.
+///
+/// Tokens without a head are not reported as disconnected.
+fn find_disconnected(graph: &DepGraph) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let mut visits = vec![Visit::Unvisited; graph.len()];
+    visits[0] = Visit::Connected;
+
+    let mut cycles = Vec::new();
+    let mut path = Vec::new();
+
+    for token in 1..graph.len() {
+        // Follow the head relations until a token is found of which
+        // the connectedness is known.
+        let mut current = token;
+        let status = loop {
+            match visits[current] {
+                Visit::Unvisited => {
+                    visits[current] = Visit::OnPath;
+                    path.push(current);
+
+                    match graph.head(current) {
+                        Some(triple) => current = triple.head(),
+                        None => break Visit::Disconnected,
+                    }
+                }
+                Visit::OnPath => {
+                    // The path loops back to `current`, the tokens from
+                    // `current` to the end of the path form a cycle.
+                    let start = path.iter().position(|&t| t == current).unwrap();
+                    let mut cycle = path.split_off(start);
+                    for &t in &cycle {
+                        visits[t] = Visit::Cycle;
+                    }
+                    cycle.sort_unstable();
+                    cycles.push(cycle);
+
+                    break Visit::Disconnected;
+                }
+                Visit::Cycle => break Visit::Disconnected,
+                status => break status,
+            }
+        };
+
+        for t in path.drain(..) {
+            visits[t] = status;
+        }
+    }
+
+    let disconnected = (1..graph.len())
+        .filter(|&token| visits[token] == Visit::Disconnected && graph.head(token).is_some())
+        .collect();
+
+    cycles.sort();
+
+    (cycles, disconnected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TreeValidator, Violation};
+    use crate::graph::{DepTriple, Sentence};
+    use crate::tests::{read_sentences, TEST_SENTENCES};
+    use crate::token::Token;
+
+    fn sentence_with_heads(heads: &[Option<usize>]) -> Sentence {
+        let mut sentence = Sentence::new();
+        for _ in heads {
+            sentence.push(Token::new("x"));
+        }
+
+        for (dependent, head) in heads.iter().enumerate() {
+            if let Some(head) = head {
+                sentence.dep_graph_mut().add_deprel(DepTriple::new(
+                    *head,
+                    Some("dep"),
+                    dependent + 1,
+                ));
+            }
+        }
+
+        sentence
+    }
+
+    #[test]
+    fn valid_trees() {
+        let validator = TreeValidator::new();
+        for sentence in read_sentences("testdata/nonprojective.conll")
+            .iter()
+            .chain(read_sentences("testdata/projective.conll").iter())
+        {
+            assert!(validator.validate(&sentence.dep_graph()).is_empty());
+        }
+
+        assert!(validator
+            .validate(&sentence_with_heads(&[Some(2), Some(0), Some(2)]).dep_graph())
+            .is_empty());
+        assert!(validator.validate(&Sentence::new().dep_graph()).is_empty());
+    }
+
+    #[test]
+    fn projective_layer() {
+        let validator = TreeValidator::new();
+        assert!(validator
+            .validate(&TEST_SENTENCES[0].dep_graph())
+            .is_empty());
+        assert_eq!(
+            validator.validate(&TEST_SENTENCES[0].proj_dep_graph()),
+            vec![Violation::MissingHead { token: 2 }]
+        );
+    }
+
+    #[test]
+    fn missing_head() {
+        let sentence = sentence_with_heads(&[None, Some(0), Some(1), Some(3)]);
+        assert_eq!(
+            TreeValidator::new().validate(&sentence.dep_graph()),
+            vec![
+                Violation::MissingHead { token: 1 },
+                Violation::Disconnected { token: 3 },
+                Violation::Disconnected { token: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn root_dependents() {
+        let sentence = sentence_with_heads(&[Some(0), Some(0), Some(2)]);
+        assert_eq!(
+            TreeValidator::new().validate(&sentence.dep_graph()),
+            vec![Violation::RootDependents {
+                dependents: vec![1, 2],
+                expected: 1
+            }]
+        );
+        assert!(TreeValidator::new()
+            .root_dependents(2)
+            .validate(&sentence.dep_graph())
+            .is_empty());
+    }
+
+    #[test]
+    fn cycles() {
+        let sentence = sentence_with_heads(&[Some(0), Some(4), Some(2), Some(3), Some(4)]);
+        assert_eq!(
+            TreeValidator::new().validate(&sentence.dep_graph()),
+            vec![
+                Violation::Cycle {
+                    tokens: vec![2, 3, 4]
+                },
+                Violation::Disconnected { token: 5 },
+            ]
+        );
+
+        let sentence = sentence_with_heads(&[Some(0), Some(2)]);
+        assert_eq!(
+            TreeValidator::new().validate(&sentence.dep_graph()),
+            vec![Violation::Cycle { tokens: vec![2] }]
+        );
+    }
+
+    #[test]
+    fn root_has_head() {
+        let mut sentence = sentence_with_heads(&[Some(0)]);
+        sentence
+            .dep_graph_mut()
+            .add_deprel(DepTriple::new(1, Some("dep"), 0));
+        assert_eq!(
+            TreeValidator::new().validate(&sentence.dep_graph()),
+            vec![Violation::RootHasHead]
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Violation::RootDependents {
+                dependents: vec![1, 3],
+                expected: 1
+            }
+            .to_string(),
+            "root has 2 dependents (1, 3), expected 1"
+        );
+    }
+}